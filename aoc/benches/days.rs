@@ -0,0 +1,163 @@
+//! Benchmarks parse, part 1 and part 2 of every day against its bundled input.
+//!
+//! Run with `cargo bench -p aoc`, optionally filtered (`-- day_09/part_2`).
+//! Besides Criterion's own output, a summary of every benchmark that ran is
+//! written to `target/criterion/aoc_report.json`.
+
+use aoc_core::{input, read_input, Answer, Solution};
+use criterion::{Criterion, SamplingMode};
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const BENCHES: [&str; 3] = ["parse", "part_1", "part_2"];
+
+/// Parts that take seconds per run, with the measurement time needed to take
+/// Criterion's minimum of ten samples of them.
+const SLOW_PARTS: [(u8, &str, Duration); 3] = [
+    (6, "part_2", Duration::from_secs(900)),
+    (7, "part_2", Duration::from_secs(60)),
+    (9, "part_2", Duration::from_secs(100)),
+];
+
+type Part<S> = fn(&<S as Solution>::Input) -> aoc_core::Result<Answer>;
+
+fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("Runner crate is inside the workspace")
+}
+
+/// Where Criterion keeps its results, following the same rules it does.
+fn criterion_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("CRITERION_HOME") {
+        return PathBuf::from(dir);
+    }
+
+    env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root().join("target"))
+        .join("criterion")
+}
+
+fn bench_day<S: Solution>(criterion: &mut Criterion) {
+    let crate_name = format!("day_{:02}", S::DAY);
+    let path = input::resolve(workspace_root().join(&crate_name), &crate_name, "input.txt");
+    let text = read_input(&path).unwrap_or_else(|err| panic!("{}: {}", crate_name, err));
+    let parsed = S::parse(&text).unwrap_or_else(|err| panic!("{}: {}", crate_name, err));
+
+    let mut group = criterion.benchmark_group(&crate_name);
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&text))));
+
+    let parts: [(&str, Part<S>); 2] = [("part_1", S::part_1), ("part_2", S::part_2)];
+    let mut slow_parts = Vec::new();
+
+    for (name, part) in parts {
+        let slow = SLOW_PARTS
+            .iter()
+            .find(|(day, slow_part, _)| *day == S::DAY && *slow_part == name);
+        if let Some((_, _, measurement_time)) = slow {
+            slow_parts.push((name, part, *measurement_time));
+            continue;
+        }
+
+        // Fast parts are cheap to try first, which skips unsolved ones.
+        match part(&parsed) {
+            Ok(Answer::Unsolved) => continue,
+            Ok(_) => {}
+            Err(err) => panic!("{} {}: {}", crate_name, name, err),
+        }
+
+        group.bench_function(name, |b| b.iter(|| part(black_box(&parsed))));
+    }
+
+    group.finish();
+
+    // Each slow part gets a group of its own under the same name, so its
+    // settings don't carry over to the benchmarks after it.
+    for (name, part, measurement_time) in slow_parts {
+        let mut group = criterion.benchmark_group(&crate_name);
+        group
+            .sample_size(10)
+            .sampling_mode(SamplingMode::Flat)
+            .measurement_time(measurement_time);
+        group.bench_function(name, |b| b.iter(|| part(black_box(&parsed))));
+        group.finish();
+    }
+}
+
+/// Collects the estimates of every benchmark Criterion updated since
+/// `started` into one JSON document, so runs can be compared by scripts.
+fn write_report(started: SystemTime) {
+    let dir = criterion_dir();
+    let mut results = Vec::new();
+
+    for day in 1..=25 {
+        let crate_name = format!("day_{:02}", day);
+
+        for bench in BENCHES {
+            let path = dir.join(&crate_name).join(bench).join("new/estimates.json");
+            let fresh = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified >= started);
+            if !fresh {
+                continue;
+            }
+
+            let estimates: Value = match fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|text| serde_json::from_str(&text).map_err(|err| err.to_string()))
+            {
+                Ok(estimates) => estimates,
+                Err(err) => {
+                    eprintln!("Skipping {}: {}", path.display(), err);
+                    continue;
+                }
+            };
+
+            results.push(json!({
+                "day": day,
+                "bench": bench,
+                "mean_ns": estimates["mean"]["point_estimate"],
+                "median_ns": estimates["median"]["point_estimate"],
+                "std_dev_ns": estimates["std_dev"]["point_estimate"],
+            }));
+        }
+    }
+
+    if results.is_empty() {
+        return;
+    }
+
+    let report = dir.join("aoc_report.json");
+    let text = serde_json::to_string_pretty(&json!({ "benchmarks": results }))
+        .expect("Report is valid JSON");
+    match fs::write(&report, text) {
+        Ok(()) => println!("Wrote {}", report.display()),
+        Err(err) => eprintln!("Could not write {}: {}", report.display(), err),
+    }
+}
+
+fn main() {
+    let started = SystemTime::now();
+    let mut criterion = Criterion::default().configure_from_args();
+
+    bench_day::<day_01::Day01>(&mut criterion);
+    bench_day::<day_02::Day02>(&mut criterion);
+    bench_day::<day_03::Day03>(&mut criterion);
+    bench_day::<day_04::Day04>(&mut criterion);
+    bench_day::<day_05::Day05>(&mut criterion);
+    bench_day::<day_06::Day06>(&mut criterion);
+    bench_day::<day_07::Day07>(&mut criterion);
+    bench_day::<day_08::Day08>(&mut criterion);
+    bench_day::<day_09::Day09>(&mut criterion);
+    bench_day::<day_10::Day10>(&mut criterion);
+    bench_day::<day_11::Day11>(&mut criterion);
+    bench_day::<day_12::Day12>(&mut criterion);
+
+    criterion.final_summary();
+    write_report(started);
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+const HEADER: &str = "\
+# Known puzzle answers, checked by `aoc verify`. `input` is the SHA-256 of the
+# puzzle input; run `aoc verify --record` to add answers for new inputs.
+";
+
+/// Known answers for every puzzle input the team has solved, stored as TOML.
+/// Answers are keyed by day, part and the hash of the input they belong to, so
+/// several people's inputs for the same day can live side by side.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Registry {
+    #[serde(default, rename = "answer")]
+    answers: Vec<KnownAnswer>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct KnownAnswer {
+    pub day: u8,
+    pub part: u8,
+    /// `input_hash` of the puzzle input.
+    pub input: String,
+    pub expected: String,
+    /// Free-form note on whose input this is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Registry {
+    /// Loads the registry, treating a missing file as an empty one.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|err| format!("Could not parse {}: {}", path.display(), err).into()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Could not read {}: {}", path.display(), err).into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut answers = self.answers.clone();
+        answers.sort_by(|a, b| (a.day, a.part, &a.input).cmp(&(b.day, b.part, &b.input)));
+
+        let text = toml::to_string_pretty(&Registry { answers })?;
+        fs::write(path, format!("{}\n{}", HEADER, text))
+            .map_err(|err| format!("Could not write {}: {}", path.display(), err).into())
+    }
+
+    pub fn lookup(&self, day: u8, part: u8, input_hash: &str) -> Option<&KnownAnswer> {
+        self.answers
+            .iter()
+            .find(|answer| answer.day == day && answer.part == part && answer.input == input_hash)
+    }
+
+    /// Adds an answer, replacing any existing one for the same day, part and input.
+    pub fn insert(&mut self, answer: KnownAnswer) {
+        self.answers.retain(|known| {
+            (known.day, known.part, &known.input) != (answer.day, answer.part, &answer.input)
+        });
+        self.answers.push(answer);
+    }
+}
+
+/// SHA-256 of a puzzle input. Line endings and trailing whitespace are
+/// normalised first so the same input hashes identically on every machine.
+pub fn input_hash(input: &str) -> String {
+    let normalised = input.replace("\r\n", "\n");
+    let digest = Sha256::digest(normalised.trim_end().as_bytes());
+
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_test_input_hash() {
+        assert_eq!(input_hash("3   4\n4   3\n"), input_hash("3   4\r\n4   3"));
+        assert_ne!(input_hash("3   4\n4   3\n"), input_hash("3   4\n4   4\n"));
+        assert_eq!(
+            input_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn answers_test_registry_roundtrip() {
+        let text = r#"
+[[answer]]
+day = 1
+part = 1
+input = "abc"
+expected = "11"
+
+[[answer]]
+day = 1
+part = 1
+input = "def"
+expected = "12"
+label = "alice"
+"#;
+        let mut registry: Registry = toml::from_str(text).unwrap();
+        assert_eq!(registry.answers.len(), 2);
+        assert_eq!(registry.lookup(1, 1, "abc").unwrap().expected, "11");
+        assert_eq!(
+            registry.lookup(1, 1, "def").unwrap().label.as_deref(),
+            Some("alice")
+        );
+        assert!(registry.lookup(1, 2, "abc").is_none());
+
+        registry.insert(KnownAnswer {
+            day: 1,
+            part: 1,
+            input: String::from("abc"),
+            expected: String::from("13"),
+            label: None,
+        });
+        assert_eq!(registry.answers.len(), 2);
+        assert_eq!(registry.lookup(1, 1, "abc").unwrap().expected, "13");
+
+        let saved: Registry = toml::from_str(&toml::to_string_pretty(&registry).unwrap()).unwrap();
+        assert_eq!(saved.answers, registry.answers);
+    }
+
+    #[test]
+    fn answers_test_missing_registry_is_empty() {
+        let registry = Registry::load(Path::new("does_not_exist.toml")).unwrap();
+        assert!(registry.answers.is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+use aoc_core::{input, solve, Answer, Solution};
+use std::path::{Path, PathBuf};
+
+pub type Solver = fn(&str, u8) -> aoc_core::Result<Answer>;
+
+pub struct Day {
+    pub number: u8,
+    pub solve: Solver,
+}
+
+impl Day {
+    const fn of<S: Solution>() -> Self {
+        Day {
+            number: S::DAY,
+            solve: solve::<S>,
+        }
+    }
+
+    /// The day's `input.txt`, from the configured inputs directory or else
+    /// the one bundled with its crate in the workspace.
+    pub fn default_input(&self) -> PathBuf {
+        let crate_name = self.crate_name();
+        input::resolve(workspace_root().join(&crate_name), &crate_name, "input.txt")
+    }
+
+    pub fn crate_name(&self) -> String {
+        format!("day_{:02}", self.number)
+    }
+}
+
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("Runner crate is inside the workspace")
+}
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+pub static DAYS: &[Day] = &[
+    Day::of::<day_01::Day01>(),
+    Day::of::<day_02::Day02>(),
+    Day::of::<day_03::Day03>(),
+    Day::of::<day_04::Day04>(),
+    Day::of::<day_05::Day05>(),
+    Day::of::<day_06::Day06>(),
+    Day::of::<day_07::Day07>(),
+    Day::of::<day_08::Day08>(),
+    Day::of::<day_09::Day09>(),
+    Day::of::<day_10::Day10>(),
+    Day::of::<day_11::Day11>(),
+    Day::of::<day_12::Day12>(),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aoc_test_days_registered_in_order() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.number as usize, i + 1);
+            assert!(day
+                .default_input()
+                .ends_with(format!("day_{:02}/input.txt", i + 1)));
+        }
+    }
+
+    #[test]
+    fn aoc_test_run_day_simple() {
+        let day = find_day(1).unwrap();
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        assert_eq!((day.solve)(input, 1).unwrap().to_string(), "11");
+        assert_eq!((day.solve)(input, 2).unwrap().to_string(), "31");
+        assert_eq!(
+            (find_day(12).unwrap().solve)("AB\nBA", 2).unwrap(),
+            Answer::Unsolved
+        );
+        assert!(find_day(0).is_none());
+        assert!(find_day(26).is_none());
+    }
+}
@@ -0,0 +1,399 @@
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Environment variable holding the adventofcode.com session cookie,
+/// overriding `session` in the config file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable naming the config file to use instead of
+/// `~/.config/aoc/config.toml`.
+pub const CONFIG_VAR: &str = "AOC_CONFIG";
+
+const YEAR: u16 = 2024;
+const BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/dpdresser/aoc_2024 input fetcher";
+
+/// Shortest time between two requests to the server, across runs.
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// The HTTP requests the fetcher makes, so tests can stand in for the server.
+pub trait HttpClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Box<dyn Error>>;
+}
+
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+impl UreqClient {
+    pub fn new() -> Self {
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+
+        UreqClient { agent }
+    }
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Box<dyn Error>> {
+        let mut request = self.agent.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let mut response = request.call()?;
+        Ok(Response {
+            status: response.status().as_u16(),
+            body: response.body_mut().read_to_string()?,
+        })
+    }
+}
+
+/// Settings read from `config.toml`. Every field is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Value of the `session` cookie on adventofcode.com.
+    pub session: Option<String>,
+    /// Where downloaded inputs are kept [default: ~/.cache/aoc_2024].
+    pub cache_dir: Option<PathBuf>,
+    /// Server to download from, e.g. a local stand-in.
+    pub base_url: Option<String>,
+    /// Shortest time between requests, in seconds.
+    pub min_interval_secs: Option<u64>,
+}
+
+impl Config {
+    /// `AOC_CONFIG`, or `aoc/config.toml` in the user's config directory.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_VAR) {
+            return Some(PathBuf::from(path));
+        }
+
+        user_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("aoc/config.toml"))
+    }
+
+    /// Loads the config, treating a missing file as an empty one.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|err| format!("Could not parse {}: {}", path.display(), err).into()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Could not read {}: {}", path.display(), err).into()),
+        }
+    }
+
+    /// The config with `AOC_SESSION` taking the place of `session` if set, and
+    /// `cache_dir` defaulting to `aoc_2024` in the user's cache directory.
+    pub fn with_env(self) -> Self {
+        Config {
+            session: env::var(SESSION_VAR).ok().or(self.session),
+            cache_dir: self
+                .cache_dir
+                .or_else(|| user_dir("XDG_CACHE_HOME", ".cache").map(|dir| dir.join("aoc_2024"))),
+            ..self
+        }
+    }
+}
+
+/// `$<var>` if set, otherwise `~/<fallback>`.
+fn user_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Cache,
+    Download,
+    /// The download failed, so the cached copy was used instead.
+    StaleCache(String),
+}
+
+/// Downloads puzzle inputs and keeps them in a cache laid out like an inputs
+/// directory, `<cache_dir>/day_XX/input.txt`, so it can be passed to
+/// `--inputs-dir` as is.
+pub struct Fetcher<C> {
+    client: C,
+    base_url: String,
+    session: Option<String>,
+    cache_dir: PathBuf,
+    min_interval: Duration,
+}
+
+impl<C: HttpClient> Fetcher<C> {
+    /// A fetcher using exactly the settings in `config`; see
+    /// [`Config::with_env`] for those taken from the environment.
+    pub fn new(client: C, config: Config) -> Result<Self, Box<dyn Error>> {
+        let cache_dir = config
+            .cache_dir
+            .ok_or("No cache directory; set cache_dir in the config file")?;
+
+        Ok(Fetcher {
+            client,
+            base_url: config.base_url.unwrap_or_else(|| String::from(BASE_URL)),
+            session: config.session.map(|session| session.trim().to_string()),
+            cache_dir,
+            min_interval: config
+                .min_interval_secs
+                .map_or(MIN_INTERVAL, Duration::from_secs),
+        })
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn cached_path(&self, day: u8) -> PathBuf {
+        self.cache_dir
+            .join(format!("day_{:02}", day))
+            .join("input.txt")
+    }
+
+    /// Returns the path of the input for `day`, downloading it unless it is
+    /// already cached. With `refresh` the input is downloaded again, and the
+    /// cached copy is only used if that fails.
+    pub fn fetch(&self, day: u8, refresh: bool) -> Result<(PathBuf, Source), Box<dyn Error>> {
+        let path = self.cached_path(day);
+        let cached = path.exists();
+        if cached && !refresh {
+            return Ok((path, Source::Cache));
+        }
+
+        match self.download(day) {
+            Ok(input) => {
+                fs::create_dir_all(path.parent().expect("Cached inputs are in a day directory"))?;
+                fs::write(&path, input)
+                    .map_err(|err| format!("Could not write {}: {}", path.display(), err))?;
+                Ok((path, Source::Download))
+            }
+            Err(err) if cached => Ok((path, Source::StaleCache(err.to_string()))),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn download(&self, day: u8) -> Result<String, Box<dyn Error>> {
+        let session = self.session.as_deref().ok_or(format!(
+            "No session token; set {} or session in the config file",
+            SESSION_VAR
+        ))?;
+
+        self.wait_for_rate_limit()?;
+
+        let url = format!("{}/{}/day/{}/input", self.base_url, YEAR, day);
+        let cookie = format!("session={}", session);
+        let response = self
+            .client
+            .get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])
+            .map_err(|err| format!("Could not download {}: {}", url, err))?;
+
+        match response.status {
+            200 if !response.body.trim().is_empty() => Ok(response.body),
+            200 => Err(format!("Server returned an empty input for day {}", day).into()),
+            400 | 500 => Err("Server rejected the session token; it may have expired".into()),
+            404 => Err(format!("Day {} is not available yet", day).into()),
+            429 => Err("Server is rate limiting requests; try again later".into()),
+            status => Err(format!("Server returned status {} for {}", status, url).into()),
+        }
+    }
+
+    /// Sleeps until `min_interval` has passed since the last request made by
+    /// any run, then records this one.
+    fn wait_for_rate_limit(&self) -> Result<(), Box<dyn Error>> {
+        let stamp = self.cache_dir.join(".last_request");
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+        let last = fs::read_to_string(&stamp)
+            .ok()
+            .and_then(|text| text.trim().parse().ok())
+            .map(Duration::from_millis);
+        if let Some(last) = last {
+            let elapsed = now.saturating_sub(last);
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+
+        fs::create_dir_all(&self.cache_dir)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        fs::write(&stamp, now.as_millis().to_string())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::time::Instant;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc_fetch_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn config(cache_dir: &Path, base_url: &str) -> Config {
+        Config {
+            session: Some(String::from("abc123")),
+            cache_dir: Some(cache_dir.to_path_buf()),
+            base_url: Some(base_url.to_string()),
+            min_interval_secs: Some(0),
+        }
+    }
+
+    /// Serves `responses` in order, one per connection, then shuts down.
+    /// Every request's head is sent back over the returned channel.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                sender
+                    .send(String::from_utf8_lossy(&request).into_owned())
+                    .unwrap();
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (base_url, receiver)
+    }
+
+    #[test]
+    fn fetch_test_download_and_cache() {
+        let cache_dir = temp_dir("download");
+        let (base_url, requests) = serve(vec![(200, "125 17\n")]);
+        let fetcher = Fetcher::new(UreqClient::new(), config(&cache_dir, &base_url)).unwrap();
+
+        let (path, source) = fetcher.fetch(11, false).unwrap();
+        assert_eq!(source, Source::Download);
+        assert_eq!(path, cache_dir.join("day_11/input.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "125 17\n");
+
+        let request = requests.recv().unwrap().to_lowercase();
+        assert!(request.starts_with("get /2024/day/11/input "));
+        assert!(request.contains("cookie: session=abc123"));
+
+        // Served from the cache without another request.
+        assert_eq!(fetcher.fetch(11, false).unwrap().1, Source::Cache);
+
+        // The server has gone away, so a refresh falls back to the cache.
+        let (_, source) = fetcher.fetch(11, true).unwrap();
+        assert!(matches!(source, Source::StaleCache(_)));
+        assert!(requests.try_recv().is_err());
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_test_errors() {
+        let cache_dir = temp_dir("errors");
+        let (base_url, _requests) = serve(vec![(404, "Not Found"), (400, "Bad Request")]);
+        let fetcher = Fetcher::new(UreqClient::new(), config(&cache_dir, &base_url)).unwrap();
+
+        let err = fetcher.fetch(25, false).unwrap_err();
+        assert_eq!(err.to_string(), "Day 25 is not available yet");
+        let err = fetcher.fetch(1, false).unwrap_err();
+        assert!(err.to_string().contains("session token"));
+        assert!(!fetcher.cached_path(1).exists());
+
+        let _ = fs::remove_dir_all(cache_dir);
+    }
+
+    #[test]
+    fn fetch_test_session_from_env() {
+        let cache_dir = temp_dir("session");
+        env::set_var(SESSION_VAR, " fromenv\n");
+
+        // Only the config given is used, whatever the environment holds.
+        let fetcher = Fetcher::new(UreqClient::new(), config(&cache_dir, BASE_URL)).unwrap();
+        assert_eq!(fetcher.session.as_deref(), Some("abc123"));
+
+        let config = config(&cache_dir, BASE_URL).with_env();
+        assert_eq!(config.cache_dir.as_deref(), Some(cache_dir.as_path()));
+        let fetcher = Fetcher::new(UreqClient::new(), config).unwrap();
+        assert_eq!(fetcher.session.as_deref(), Some("fromenv"));
+
+        env::remove_var(SESSION_VAR);
+    }
+
+    /// Answers every request itself and records when it was made.
+    struct FakeClient {
+        requests: RefCell<Vec<Instant>>,
+    }
+
+    impl HttpClient for FakeClient {
+        fn get(&self, _url: &str, _headers: &[(&str, &str)]) -> Result<Response, Box<dyn Error>> {
+            self.requests.borrow_mut().push(Instant::now());
+            Ok(Response {
+                status: 200,
+                body: String::from("1 2\n"),
+            })
+        }
+    }
+
+    #[test]
+    fn fetch_test_rate_limit() {
+        let cache_dir = temp_dir("rate_limit");
+        let client = FakeClient {
+            requests: RefCell::new(Vec::new()),
+        };
+        let mut fetcher = Fetcher::new(client, config(&cache_dir, BASE_URL)).unwrap();
+        fetcher.min_interval = Duration::from_millis(300);
+
+        fetcher.fetch(1, true).unwrap();
+        fetcher.fetch(1, true).unwrap();
+
+        let requests = fetcher.client.requests.borrow();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1] - requests[0] >= Duration::from_millis(250));
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+}
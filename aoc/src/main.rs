@@ -0,0 +1,388 @@
+mod answers;
+mod days;
+mod fetch;
+mod scaffold;
+mod verify;
+
+use answers::{input_hash, KnownAnswer, Registry};
+use aoc_core::{input, read_input, read_stdin, Answer};
+use clap::{Args, Parser, Subcommand};
+use days::{find_day, workspace_root, Day, DAYS};
+use fetch::{Config, Fetcher, Source, UreqClient};
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use verify::{check_input, Status};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2024 solutions")]
+struct Cli {
+    /// Directory holding inputs as <dir>/day_XX/input.txt [env: AOC_INPUTS_DIR]
+    #[arg(long, global = true)]
+    inputs_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solution for one day, or for every day with --all
+    Run(RunArgs),
+    /// Check answers for every known input against the answers registry
+    Verify(VerifyArgs),
+    /// Create and register the crate for a new day
+    New(NewArgs),
+    /// Download a day's input into the local cache
+    Fetch(FetchArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day to run
+    #[arg(
+        required_unless_present = "all",
+        conflicts_with = "all",
+        value_parser = clap::value_parser!(u8).range(1..=25)
+    )]
+    day: Option<u8>,
+
+    /// Run every day and print a summary table
+    #[arg(long, conflicts_with = "input")]
+    all: bool,
+
+    /// Only run the given part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Puzzle input to use instead of the day's input.txt, or - for stdin
+    #[arg(long)]
+    input: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Only verify this day
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+
+    /// Only verify the given part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Additional inputs laid out as <dir>/day_XX/input.txt, e.g. one per
+    /// team member. May be given more than once
+    #[arg(long = "inputs", value_name = "DIR")]
+    inputs: Vec<PathBuf>,
+
+    /// Answers registry to check against [default: answers.toml in the workspace]
+    #[arg(long)]
+    answers: Option<PathBuf>,
+
+    /// Add the answers for unknown inputs to the registry
+    #[arg(long)]
+    record: bool,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    /// Day to create
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+}
+
+#[derive(Args)]
+struct FetchArgs {
+    /// Day to fetch
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+
+    /// Download the input again even if it is cached
+    #[arg(long)]
+    refresh: bool,
+}
+
+struct PartResult {
+    answer: Result<Answer, String>,
+    elapsed: Duration,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.inputs_dir.is_some() {
+        input::set_inputs_dir(cli.inputs_dir);
+    }
+
+    let result = match cli.command {
+        Command::Run(args) => run(args),
+        Command::Verify(args) => verify(args),
+        Command::New(args) => new_day(args),
+        Command::Fetch(args) => fetch(args),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Returns whether every requested part produced an answer.
+fn run(args: RunArgs) -> Result<bool, Box<dyn Error>> {
+    let parts: Vec<u8> = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    if args.all {
+        return Ok(run_all(&parts));
+    }
+
+    let number = args.day.expect("clap requires a day without --all");
+    let day = find_day(number).ok_or(format!("Day {} has not been solved yet", number))?;
+    let input = match args.input {
+        Some(path) if path.as_os_str() == "-" => read_stdin()?,
+        Some(path) => read_input(path)?,
+        None => read_input(day.default_input())?,
+    };
+
+    println!("Day {:02}", day.number);
+    let mut success = true;
+    for &part in &parts {
+        let result = run_part(day, part, &input);
+        match result.answer {
+            Ok(Answer::Unsolved) => println!("  Part {}: {}", part, Answer::Unsolved),
+            Ok(answer) => println!("  Part {}: {} ({:.2?})", part, answer, result.elapsed),
+            Err(err) => {
+                println!("  Part {}: {}", part, err);
+                success = false;
+            }
+        }
+    }
+
+    Ok(success)
+}
+
+fn run_all(parts: &[u8]) -> bool {
+    let mut success = true;
+    let mut total = Duration::ZERO;
+
+    println!(
+        "{:>3} | {:<16} | {:>10} | {:<16} | {:>10}",
+        "Day", "Part 1", "Time", "Part 2", "Time"
+    );
+    println!("{:-<4}+{:-<18}+{:-<12}+{:-<18}+{:-<11}", "", "", "", "", "");
+
+    for day in DAYS {
+        let mut cells = Vec::new();
+
+        match read_input(day.default_input()) {
+            Ok(input) => {
+                for part in 1..=2 {
+                    if !parts.contains(&part) {
+                        cells.push((String::from("-"), String::new()));
+                        continue;
+                    }
+
+                    let result = run_part(day, part, &input);
+                    total += result.elapsed;
+                    match result.answer {
+                        Ok(Answer::Unsolved) => {
+                            cells.push((Answer::Unsolved.to_string(), String::new()))
+                        }
+                        Ok(answer) => {
+                            cells.push((answer.to_string(), format!("{:.2?}", result.elapsed)))
+                        }
+                        Err(err) => {
+                            success = false;
+                            cells.push((err, String::new()));
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                success = false;
+                cells.push((err.to_string(), String::new()));
+                cells.push((String::new(), String::new()));
+            }
+        }
+
+        println!(
+            "{:>3} | {:<16} | {:>10} | {:<16} | {:>10}",
+            format!("{:02}", day.number),
+            cells[0].0,
+            cells[0].1,
+            cells[1].0,
+            cells[1].1
+        );
+    }
+
+    println!();
+    println!("Total time: {:.2?}", total);
+
+    success
+}
+
+/// Returns whether no answer disagreed with the registry or failed to solve.
+fn verify(args: VerifyArgs) -> Result<bool, Box<dyn Error>> {
+    let answers_path = args
+        .answers
+        .unwrap_or_else(|| workspace_root().join("answers.toml"));
+    let mut registry = Registry::load(&answers_path)?;
+
+    let days: Vec<&Day> = match args.day {
+        Some(number) => {
+            vec![find_day(number).ok_or(format!("Day {} has not been solved yet", number))?]
+        }
+        None => DAYS.iter().collect(),
+    };
+    let parts: Vec<u8> = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+    let mut recorded = 0;
+
+    for day in days {
+        let mut sources = vec![(day.default_input(), None)];
+        for dir in &args.inputs {
+            let path = dir.join(day.crate_name()).join("input.txt");
+            if path.exists() {
+                let label = dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned());
+                sources.push((path, label));
+            }
+        }
+
+        for (path, label) in sources {
+            let input = match read_input(&path) {
+                Ok(input) => input,
+                Err(err) => {
+                    println!("{:<8} day {:02}         {}", "ERROR", day.number, err);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let hash = input_hash(&input);
+
+            for check in check_input(&registry, day, &input, &hash, &parts) {
+                let answer = check
+                    .answer
+                    .as_ref()
+                    .map_or(String::new(), |answer| answer.to_string());
+                let (status, detail) = match &check.status {
+                    Status::Pass => {
+                        passed += 1;
+                        ("PASS", answer.clone())
+                    }
+                    Status::Fail { expected } => {
+                        failed += 1;
+                        ("FAIL", format!("{} (expected {})", answer, expected))
+                    }
+                    Status::Unknown => {
+                        unknown += 1;
+                        ("UNKNOWN", answer.clone())
+                    }
+                    Status::Unsolved => ("UNSOLVED", String::new()),
+                    Status::Error(err) => {
+                        failed += 1;
+                        ("ERROR", err.clone())
+                    }
+                };
+
+                println!(
+                    "{:<8} day {:02} part {}  {:<32} {}",
+                    status,
+                    day.number,
+                    check.part,
+                    detail,
+                    path.strip_prefix(workspace_root())
+                        .unwrap_or(&path)
+                        .display()
+                );
+
+                if args.record && check.status == Status::Unknown {
+                    registry.insert(KnownAnswer {
+                        day: day.number,
+                        part: check.part,
+                        input: hash.clone(),
+                        expected: answer,
+                        label: label.clone(),
+                    });
+                    recorded += 1;
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{} passed, {} failed, {} unknown", passed, failed, unknown);
+
+    if recorded > 0 {
+        registry.save(&answers_path)?;
+        println!(
+            "Recorded {} new answers in {}",
+            recorded,
+            answers_path.display()
+        );
+    }
+
+    Ok(failed == 0)
+}
+
+fn new_day(args: NewArgs) -> Result<bool, Box<dyn Error>> {
+    let root = workspace_root();
+    let changed = scaffold::new_day(root, args.day)?;
+
+    for path in changed {
+        println!("  {}", path.strip_prefix(root).unwrap_or(&path).display());
+    }
+    println!();
+    println!(
+        "Created day_{:02}; paste the example into day_{:02}/input_simple.txt",
+        args.day, args.day
+    );
+
+    Ok(true)
+}
+
+fn fetch(args: FetchArgs) -> Result<bool, Box<dyn Error>> {
+    let config = match Config::path() {
+        Some(path) => Config::load(&path)?,
+        None => Config::default(),
+    };
+    let fetcher = Fetcher::new(UreqClient::new(), config.with_env())?;
+
+    let (path, source) = fetcher.fetch(args.day, args.refresh)?;
+    match source {
+        Source::Cache => println!("Already cached: {}", path.display()),
+        Source::Download => println!("Downloaded: {}", path.display()),
+        Source::StaleCache(err) => {
+            eprintln!("warning: {}", err);
+            println!("Using cached copy: {}", path.display());
+        }
+    }
+    println!(
+        "Run it with --inputs-dir {} or --input {}",
+        fetcher.cache_dir().display(),
+        path.display()
+    );
+
+    Ok(true)
+}
+
+fn run_part(day: &Day, part: u8, input: &str) -> PartResult {
+    let start = Instant::now();
+    let answer = (day.solve)(input, part).map_err(|err| format!("error: {}", err));
+
+    PartResult {
+        answer,
+        elapsed: start.elapsed(),
+    }
+}
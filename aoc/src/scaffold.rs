@@ -0,0 +1,185 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/Cargo.toml.template");
+const LIB_TEMPLATE: &str = include_str!("../templates/lib.rs.template");
+const EXAMPLES_TEMPLATE: &str = include_str!("../templates/examples.toml.template");
+
+/// Files that list every day, each with the prefix of the lines to extend
+/// and the line to add for a new day.
+const REGISTRIES: [(&str, &str, &str); 4] = [
+    ("Cargo.toml", "\"day_", "\"{{crate}}\","),
+    (
+        "aoc/Cargo.toml",
+        "day_",
+        "{{crate}} = { path = \"../{{crate}}\" }",
+    ),
+    (
+        "aoc/src/days.rs",
+        "Day::of::<day_",
+        "Day::of::<{{crate}}::{{Day}}>(),",
+    ),
+    (
+        "aoc/benches/days.rs",
+        "bench_day::<day_",
+        "bench_day::<{{crate}}::{{Day}}>(&mut criterion);",
+    ),
+];
+
+/// Creates the `day_XX` crate for `day` in the workspace at `root` and
+/// registers it in the workspace, the runner and the benchmarks. Returns every
+/// file created or changed.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let crate_name = format!("day_{:02}", day);
+    let crate_dir = root.join(&crate_name);
+    if crate_dir.exists() {
+        return Err(format!("{} already exists", crate_dir.display()).into());
+    }
+
+    // Check every registry before touching anything, so a failure leaves the
+    // workspace as it was.
+    let mut registries = Vec::new();
+    for (file, prefix, line) in REGISTRIES {
+        let path = root.join(file);
+        let text = fs::read_to_string(&path)
+            .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+        let text = register(&text, prefix, day, &render(line, day)).ok_or(format!(
+            "Found no lines starting with {} in {}",
+            prefix,
+            path.display()
+        ))?;
+        registries.push((path, text));
+    }
+
+    let files = [
+        (crate_dir.join("Cargo.toml"), render(CARGO_TEMPLATE, day)),
+        (crate_dir.join("src/lib.rs"), render(LIB_TEMPLATE, day)),
+        (
+            crate_dir.join("examples.toml"),
+            render(EXAMPLES_TEMPLATE, day),
+        ),
+        (crate_dir.join("input.txt"), String::new()),
+        (crate_dir.join("input_simple.txt"), String::new()),
+    ];
+
+    let mut changed = Vec::new();
+    fs::create_dir_all(crate_dir.join("src"))?;
+    for (path, text) in files.into_iter().chain(registries) {
+        fs::write(&path, text)
+            .map_err(|err| format!("Could not write {}: {}", path.display(), err))?;
+        changed.push(path);
+    }
+
+    Ok(changed)
+}
+
+fn render(template: &str, day: u8) -> String {
+    template
+        .replace("{{crate}}", &format!("day_{:02}", day))
+        .replace("{{Day}}", &format!("Day{:02}", day))
+        .replace("{{day}}", &day.to_string())
+}
+
+/// Inserts `line` among the lines starting with `prefix`, keeping them ordered
+/// by the day number that follows `day_`, and indented like its neighbours.
+/// Returns `None` if no line starts with `prefix`.
+fn register(text: &str, prefix: &str, day: u8, line: &str) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let entries: Vec<(usize, Option<u8>)> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.trim_start().starts_with(prefix))
+        .map(|(i, line)| (i, day_number(line)))
+        .collect();
+
+    let (first, _) = *entries.first()?;
+    let previous = entries
+        .iter()
+        .rev()
+        .find(|(_, number)| number.is_some_and(|number| number < day))
+        .map(|(i, _)| *i);
+    let index = previous.map_or(first, |i| i + 1);
+
+    let neighbour = lines[previous.unwrap_or(first)];
+    let indent = &neighbour[..neighbour.len() - neighbour.trim_start().len()];
+
+    let mut result: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    result.insert(index, format!("{}{}", indent, line));
+
+    Some(result.join("\n") + "\n")
+}
+
+fn day_number(line: &str) -> Option<u8> {
+    let (_, rest) = line.split_once("day_")?;
+    rest.get(..2)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffold_test_register() {
+        let members = "members = [\n    \"aoc\",\n    \"day_01\", \n    \"day_03\",\n]\n";
+
+        assert_eq!(
+            register(members, "\"day_", 2, "\"day_02\",").unwrap(),
+            "members = [\n    \"aoc\",\n    \"day_01\", \n    \"day_02\",\n    \"day_03\",\n]\n"
+        );
+        assert_eq!(
+            register(members, "\"day_", 4, "\"day_04\",").unwrap(),
+            "members = [\n    \"aoc\",\n    \"day_01\", \n    \"day_03\",\n    \"day_04\",\n]\n"
+        );
+        assert!(register("members = []\n", "\"day_", 4, "\"day_04\",").is_none());
+    }
+
+    #[test]
+    fn scaffold_test_render() {
+        let lib = render(LIB_TEMPLATE, 13);
+        assert!(lib.contains("pub struct Day13;"));
+        assert!(lib.contains("const DAY: u8 = 13;"));
+        assert!(lib.contains("example_tests!(Day13);"));
+        assert!(!lib.contains("{{"));
+    }
+
+    #[test]
+    fn scaffold_test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+        fs::create_dir_all(root.join("aoc/src")).unwrap();
+        fs::create_dir_all(root.join("aoc/benches")).unwrap();
+        fs::create_dir_all(root.join("day_12")).unwrap();
+        fs::write(root.join("Cargo.toml"), "members = [\n    \"day_12\",\n]\n").unwrap();
+        fs::write(
+            root.join("aoc/Cargo.toml"),
+            "[dependencies]\nday_12 = { path = \"../day_12\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc/src/days.rs"),
+            "pub static DAYS: &[Day] = &[\n    Day::of::<day_12::Day12>(),\n];\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc/benches/days.rs"),
+            "    bench_day::<day_12::Day12>(&mut criterion);\n",
+        )
+        .unwrap();
+
+        let changed = new_day(&root, 13).unwrap();
+        assert_eq!(changed.len(), 9);
+        assert!(root.join("day_13/src/lib.rs").exists());
+        assert!(fs::read_to_string(root.join("aoc/src/days.rs"))
+            .unwrap()
+            .contains("    Day::of::<day_13::Day13>(),\n];"));
+        assert!(new_day(&root, 12).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+
+        // The runner's own tests must still pass once any day is added.
+        let runner = include_str!("days.rs");
+        for day in 1..=25 {
+            assert!(!runner.contains(&format!("find_day({}).is_none()", day)));
+        }
+    }
+}
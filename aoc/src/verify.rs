@@ -0,0 +1,117 @@
+use crate::answers::Registry;
+use crate::days::Day;
+use aoc_core::Answer;
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    /// The answer matches the registered one.
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// No answer is registered for this input yet.
+    Unknown,
+    /// The day has no solution for this part.
+    Unsolved,
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub status: Status,
+}
+
+/// Solves the requested parts of `day` for `input` and compares each answer
+/// against the registry entry for `input_hash`.
+pub fn check_input(
+    registry: &Registry,
+    day: &Day,
+    input: &str,
+    input_hash: &str,
+    parts: &[u8],
+) -> Vec<Check> {
+    parts
+        .iter()
+        .map(|&part| {
+            let known = registry.lookup(day.number, part, input_hash);
+
+            match (day.solve)(input, part) {
+                Ok(Answer::Unsolved) => Check {
+                    part,
+                    answer: None,
+                    status: Status::Unsolved,
+                },
+                Ok(answer) => {
+                    let status = match known {
+                        Some(known) if known.expected == answer.to_string() => Status::Pass,
+                        Some(known) => Status::Fail {
+                            expected: known.expected.clone(),
+                        },
+                        None => Status::Unknown,
+                    };
+
+                    Check {
+                        part,
+                        answer: Some(answer),
+                        status,
+                    }
+                }
+                Err(err) => Check {
+                    part,
+                    answer: None,
+                    status: Status::Error(err.to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::{input_hash, KnownAnswer};
+    use crate::days::find_day;
+
+    #[test]
+    fn verify_test_check_input() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let hash = input_hash(input);
+
+        let mut registry = Registry::default();
+        registry.insert(KnownAnswer {
+            day: 1,
+            part: 1,
+            input: hash.clone(),
+            expected: String::from("11"),
+            label: None,
+        });
+        registry.insert(KnownAnswer {
+            day: 1,
+            part: 2,
+            input: hash.clone(),
+            expected: String::from("30"),
+            label: None,
+        });
+
+        let checks = check_input(&registry, find_day(1).unwrap(), input, &hash, &[1, 2]);
+        assert_eq!(checks[0].status, Status::Pass);
+        assert_eq!(
+            checks[1].status,
+            Status::Fail {
+                expected: String::from("30")
+            }
+        );
+
+        let checks = check_input(&registry, find_day(1).unwrap(), "1 2\n", "other", &[1]);
+        assert_eq!(checks[0].status, Status::Unknown);
+        assert_eq!(checks[0].answer, Some(Answer::Signed(1)));
+
+        let checks = check_input(&registry, find_day(1).unwrap(), "1 x\n", "bad", &[1]);
+        assert!(matches!(checks[0].status, Status::Error(_)));
+
+        let checks = check_input(&registry, find_day(12).unwrap(), "AB\nBA\n", "day12", &[2]);
+        assert_eq!(checks[0].status, Status::Unsolved);
+    }
+}
@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum AocError {
+    /// The puzzle input could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A line of the puzzle input is malformed. `line` and `column` are 1-based.
+    Parse {
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// The input parsed, but breaks one of the puzzle's rules.
+    Invalid(String),
+}
+
+impl AocError {
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        AocError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub fn parse(line: usize, column: usize, text: &str, message: impl fmt::Display) -> Self {
+        AocError::Parse {
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        AocError::Invalid(message.into())
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::Io { path, source } => {
+                write!(f, "Could not read from file {}: {}", path.display(), source)
+            }
+            AocError::Parse {
+                line,
+                column,
+                text,
+                message,
+            } => write!(
+                f,
+                "Line {}, column {}: {}: {:?}",
+                line, column, message, text
+            ),
+            AocError::Invalid(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Parses `field`, a subslice of `line`, reporting its position in the input
+/// if it is malformed. `line_index` is the 0-based index of `line`.
+pub fn parse_field<T>(line_index: usize, line: &str, field: &str) -> Result<T, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    field
+        .trim()
+        .parse()
+        .map_err(|err| AocError::parse(line_index + 1, column_of(line, field), line, err))
+}
+
+/// Parses every whitespace-separated field of `line`.
+pub fn parse_fields<T>(line_index: usize, line: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    line.split_whitespace()
+        .map(|field| parse_field(line_index, line, field))
+        .collect()
+}
+
+/// 1-based character column at which `field` starts within `line`. Falls back
+/// to column 1 if `field` was not sliced out of `line`.
+pub fn column_of(line: &str, field: &str) -> usize {
+    let offset = (field.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    let within_line = offset
+        .checked_add(field.len())
+        .is_some_and(|end| end <= line.len());
+
+    if within_line && line.is_char_boundary(offset) {
+        line[..offset].chars().count() + 1
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_test_parse_fields() {
+        let numbers: Vec<i32> = parse_fields(0, "7 6 4 2 1").unwrap();
+        assert_eq!(numbers, vec![7, 6, 4, 2, 1]);
+
+        let err = parse_fields::<i32>(4, "7 6 x 2 1").unwrap_err();
+        match &err {
+            AocError::Parse {
+                line, column, text, ..
+            } => assert_eq!((*line, *column, text.as_str()), (5, 5, "7 6 x 2 1")),
+            _ => panic!("expected a parse error"),
+        }
+        assert_eq!(
+            err.to_string(),
+            "Line 5, column 5: invalid digit found in string: \"7 6 x 2 1\""
+        );
+    }
+
+    #[test]
+    fn error_test_column_of() {
+        let line = "47|53";
+        let mut fields = line.split('|');
+        fields.next();
+        assert_eq!(column_of(line, fields.next().unwrap()), 4);
+        assert_eq!(column_of(line, &String::from("53")), 1);
+    }
+
+    #[test]
+    fn error_test_io() {
+        let err = crate::read_input("does_not_exist.txt").unwrap_err();
+        assert!(matches!(err, AocError::Io { .. }));
+        assert!(err.source().is_some());
+    }
+}
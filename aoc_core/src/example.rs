@@ -0,0 +1,19 @@
+use crate::{solve, Solution};
+
+/// Checks one answer of an example, as generated by [`example_tests!`].
+///
+/// [`example_tests!`]: crate::example_tests
+#[track_caller]
+pub fn check_example<S: Solution>(name: &str, input: &str, part: u8, expected: &str) {
+    match solve::<S>(input, part) {
+        Ok(answer) => assert_eq!(
+            answer.to_string(),
+            expected,
+            "Day {:02} example {} part {}",
+            S::DAY,
+            name,
+            part
+        ),
+        Err(err) => panic!("Day {:02} example {} part {}: {}", S::DAY, name, part, err),
+    }
+}
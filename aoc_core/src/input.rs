@@ -0,0 +1,137 @@
+use crate::{AocError, Result};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Environment variable naming a directory that holds every day's inputs as
+/// `<dir>/day_XX/<file>`, replacing the files bundled with each crate.
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS_DIR";
+
+static INPUTS_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Overrides the inputs directory for the rest of the process, taking
+/// precedence over `AOC_INPUTS_DIR`. `None` restores the default.
+pub fn set_inputs_dir(dir: Option<PathBuf>) {
+    *INPUTS_DIR.write().expect("Inputs directory lock poisoned") = dir;
+}
+
+/// The configured inputs directory, if any.
+pub fn inputs_dir() -> Option<PathBuf> {
+    let configured = INPUTS_DIR
+        .read()
+        .expect("Inputs directory lock poisoned")
+        .clone();
+
+    configured.or_else(|| env::var_os(INPUTS_DIR_VAR).map(PathBuf::from))
+}
+
+/// Resolves `path` for the crate at `crate_dir`. Absolute paths are kept;
+/// relative ones are looked up in the configured inputs directory, or in
+/// the crate itself when none is set. Usually called via [`input_path!`].
+///
+/// [`input_path!`]: crate::input_path
+pub fn resolve(crate_dir: impl AsRef<Path>, crate_name: &str, path: impl AsRef<Path>) -> PathBuf {
+    resolve_in(
+        inputs_dir().as_deref(),
+        crate_dir.as_ref(),
+        crate_name,
+        path.as_ref(),
+    )
+}
+
+fn resolve_in(
+    inputs_dir: Option<&Path>,
+    crate_dir: &Path,
+    crate_name: &str,
+    path: &Path,
+) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    match inputs_dir {
+        Some(dir) => dir.join(crate_name).join(path),
+        None => crate_dir.join(path),
+    }
+}
+
+/// Resolves an input file relative to the calling crate rather than the
+/// current working directory.
+#[macro_export]
+macro_rules! input_path {
+    ($path:expr) => {
+        $crate::input::resolve(env!("CARGO_MANIFEST_DIR"), env!("CARGO_PKG_NAME"), $path)
+    };
+}
+
+pub fn read_input(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    fs::read_to_string(path).map_err(|err| AocError::io(path, err))
+}
+
+pub fn read_from(mut reader: impl Read) -> Result<String> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(|err| AocError::io("<reader>", err))?;
+
+    Ok(text)
+}
+
+pub fn read_stdin() -> Result<String> {
+    let mut text = String::new();
+    io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|err| AocError::io("<stdin>", err))?;
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_test_resolve_in() {
+        let crate_dir = Path::new("/work/aoc_2024/day_05");
+
+        assert_eq!(
+            resolve_in(None, crate_dir, "day_05", Path::new("input.txt")),
+            Path::new("/work/aoc_2024/day_05/input.txt")
+        );
+        assert_eq!(
+            resolve_in(
+                Some(Path::new("/inputs/alice")),
+                crate_dir,
+                "day_05",
+                Path::new("input.txt")
+            ),
+            Path::new("/inputs/alice/day_05/input.txt")
+        );
+        assert_eq!(
+            resolve_in(
+                Some(Path::new("/inputs/alice")),
+                crate_dir,
+                "day_05",
+                Path::new("/tmp/input.txt")
+            ),
+            Path::new("/tmp/input.txt")
+        );
+    }
+
+    #[test]
+    fn input_test_input_path() {
+        let path = crate::input_path!("src/input.rs");
+        assert!(path.ends_with("aoc_core/src/input.rs"));
+        assert!(read_input(path)
+            .unwrap()
+            .contains("macro_rules! input_path"));
+    }
+
+    #[test]
+    fn input_test_read_from() {
+        assert_eq!(read_from("125 17".as_bytes()).unwrap(), "125 17");
+    }
+}
@@ -0,0 +1,14 @@
+mod answer;
+mod error;
+mod example;
+pub mod input;
+mod solution;
+
+pub use answer::Answer;
+pub use aoc_macros::example_tests;
+pub use error::{column_of, parse_field, parse_fields, AocError};
+pub use example::check_example;
+pub use input::{read_from, read_input, read_stdin};
+pub use solution::{solve, Solution};
+
+pub type Result<T> = std::result::Result<T, AocError>;
@@ -0,0 +1,79 @@
+use crate::{read_from, read_input, read_stdin, Answer, AocError, Result};
+use std::io::Read;
+use std::path::Path;
+
+/// A puzzle day: how to parse its input and how to answer each part.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn parse_file(path: impl AsRef<Path>) -> Result<Self::Input> {
+        Self::parse(&read_input(path)?)
+    }
+
+    fn parse_reader(reader: impl Read) -> Result<Self::Input> {
+        Self::parse(&read_from(reader)?)
+    }
+
+    fn parse_stdin() -> Result<Self::Input> {
+        Self::parse(&read_stdin()?)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer>;
+
+    fn part_2(_input: &Self::Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+}
+
+/// Parses `input` and answers the requested part of `S`.
+pub fn solve<S: Solution>(input: &str, part: u8) -> Result<Answer> {
+    let input = S::parse(input)?;
+
+    match part {
+        1 => S::part_1(&input),
+        2 => S::part_2(&input),
+        _ => Err(AocError::invalid(format!("Part {} does not exist", part))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+
+        type Input = Vec<u64>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            crate::parse_fields(0, input)
+        }
+
+        fn part_1(input: &Self::Input) -> Result<Answer> {
+            Ok(input.iter().sum::<u64>().into())
+        }
+    }
+
+    #[test]
+    fn solution_test_solve() {
+        assert_eq!(solve::<Sum>("1 2 3", 1).unwrap(), Answer::Unsigned(6));
+        assert_eq!(solve::<Sum>("1 2 3", 2).unwrap(), Answer::Unsolved);
+        assert!(solve::<Sum>("1 2 3", 3).is_err());
+        assert!(solve::<Sum>("1 two 3", 1).is_err());
+    }
+
+    #[test]
+    fn solution_test_parse_sources() {
+        assert_eq!(Sum::parse_reader("4 5".as_bytes()).unwrap(), vec![4, 5]);
+        assert!(matches!(
+            Sum::parse_file("does_not_exist.txt"),
+            Err(AocError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,297 @@
+use aoc_core::AocError;
+use ndarray::Array2;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// Row / column offsets for the four orthogonal neighbours.
+pub const DIRECTIONS_4: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Row / column offsets for the four orthogonal and four diagonal neighbours.
+pub const DIRECTIONS_8: [(isize, isize); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Moves `position` by `direction` within a grid of `(rows, columns)`,
+/// returning `None` if that leaves it. For arrays not wrapped in a [`Grid`].
+pub fn offset(
+    dim: (usize, usize),
+    position: (usize, usize),
+    direction: (isize, isize),
+) -> Option<(usize, usize)> {
+    let row = position.0.checked_add_signed(direction.0)?;
+    let column = position.1.checked_add_signed(direction.1)?;
+
+    if row < dim.0 && column < dim.1 {
+        Some((row, column))
+    } else {
+        None
+    }
+}
+
+/// A rectangular grid of cells addressed by `(row, column)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+impl Grid<char> {
+    /// Parses a block of text into a grid of its characters.
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        Self::parse_with(input, Ok::<char, AocError>)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses a block of text into a grid, converting every character with
+    /// `parse_cell`. Rows of differing lengths are rejected rather than padded.
+    pub fn parse_with<F, E>(input: &str, mut parse_cell: F) -> Result<Self, AocError>
+    where
+        F: FnMut(char) -> Result<T, E>,
+        E: fmt::Display,
+    {
+        let rows: Vec<&str> = input.trim_end_matches(['\n', '\r']).lines().collect();
+        let nrows = rows.len();
+        let ncols = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Vec::with_capacity(nrows * ncols);
+
+        for (i, row) in rows.iter().enumerate() {
+            let row_length = row.chars().count();
+            if row_length != ncols {
+                return Err(AocError::parse(
+                    i + 1,
+                    row_length.min(ncols) + 1,
+                    row,
+                    format!("Row has {} columns, expected {}", row_length, ncols),
+                ));
+            }
+
+            for (j, letter) in row.chars().enumerate() {
+                let cell = parse_cell(letter).map_err(|err| {
+                    AocError::parse(
+                        i + 1,
+                        j + 1,
+                        row,
+                        format!("Invalid cell {:?}: {}", letter, err),
+                    )
+                })?;
+                cells.push(cell);
+            }
+        }
+
+        let cells = Array2::from_shape_vec((nrows, ncols), cells)
+            .expect("Every row has been checked to have ncols cells");
+
+        Ok(Self { cells })
+    }
+
+    pub fn from_array(cells: Array2<T>) -> Self {
+        Self { cells }
+    }
+
+    pub fn as_array(&self) -> &Array2<T> {
+        &self.cells
+    }
+
+    pub fn into_array(self) -> Array2<T> {
+        self.cells
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.cells.nrows()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.cells.ncols()
+    }
+
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        self.cells.get(position)
+    }
+
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        self.cells.get_mut(position)
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells.indexed_iter()
+    }
+
+    /// Moves `position` by `direction`, returning `None` if that leaves the grid.
+    pub fn offset(
+        &self,
+        position: (usize, usize),
+        direction: (isize, isize),
+    ) -> Option<(usize, usize)> {
+        offset(self.cells.dim(), position, direction)
+    }
+
+    /// In-bounds neighbours of `position` for the given set of directions.
+    pub fn neighbours<'a>(
+        &'a self,
+        position: (usize, usize),
+        directions: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        directions
+            .iter()
+            .filter_map(move |&direction| self.offset(position, direction))
+    }
+
+    pub fn neighbours_4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(position, &DIRECTIONS_4)
+    }
+
+    pub fn neighbours_8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(position, &DIRECTIONS_8)
+    }
+
+    /// Renders the grid back to text, one line per row.
+    pub fn render_with<F>(&self, mut render_cell: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        let mut text = String::with_capacity(self.nrows() * (self.ncols() + 1));
+
+        for row in self.cells.rows() {
+            text.extend(row.iter().map(&mut render_cell));
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Position of the first cell equal to `target`, in row-major order.
+    pub fn find(&self, target: &T) -> Option<(usize, usize)> {
+        self.indexed_iter()
+            .find(|(_, cell)| *cell == target)
+            .map(|(position, _)| position)
+    }
+
+    /// Positions of every cell equal to `target`, in row-major order.
+    pub fn find_all(&self, target: &T) -> Vec<(usize, usize)> {
+        self.indexed_iter()
+            .filter(|(_, cell)| *cell == target)
+            .map(|(position, _)| position)
+            .collect()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &T {
+        &self.cells[position]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        &mut self.cells[position]
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render_with(|&c| c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    #[test]
+    fn grid_test_parse() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!((grid.nrows(), grid.ncols()), (8, 8));
+        assert_eq!(grid[(0, 0)], '8');
+        assert_eq!(grid[(7, 7)], '2');
+        assert_eq!(grid.to_string(), INPUT);
+    }
+
+    #[test]
+    fn grid_test_parse_with() {
+        let grid = Grid::parse_with(INPUT, |c| c.to_digit(10).ok_or("not a digit")).unwrap();
+        assert_eq!(grid[(2, 3)], 3);
+
+        let err = Grid::parse_with("12\n3x", |c| c.to_digit(10).ok_or("not a digit"));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Line 2, column 2: Invalid cell 'x': not a digit: \"3x\""
+        );
+    }
+
+    #[test]
+    fn grid_test_ragged_input() {
+        let err = Grid::parse("abc\nab\nabc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 2, column 3: Row has 2 columns, expected 3: \"ab\""
+        );
+    }
+
+    #[test]
+    fn grid_test_empty_input() {
+        let grid = Grid::parse("").unwrap();
+        assert_eq!((grid.nrows(), grid.ncols()), (0, 0));
+        assert_eq!(grid.find(&'a'), None);
+    }
+
+    #[test]
+    fn grid_test_neighbours() {
+        let grid = Grid::parse("abc\ndef\nghi").unwrap();
+
+        let corner: Vec<_> = grid.neighbours_4((0, 0)).collect();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+
+        let corner: Vec<_> = grid.neighbours_8((2, 2)).collect();
+        assert_eq!(corner, vec![(1, 2), (2, 1), (1, 1)]);
+
+        assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+        assert_eq!(grid.offset((1, 2), (0, 1)), None);
+        assert_eq!(offset((3, 4), (1, 2), (0, 1)), Some((1, 3)));
+        assert_eq!(offset((3, 4), (0, 2), (-1, 0)), None);
+    }
+
+    #[test]
+    fn grid_test_find() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.find(&'9'), Some((0, 1)));
+        assert_eq!(grid.find(&'x'), None);
+        assert_eq!(grid.find_all(&'9').len(), 7);
+    }
+
+    #[test]
+    fn grid_test_render_with() {
+        let grid = Grid::parse_with("1.\n.2", |c| Ok::<_, AocError>(c.to_digit(10))).unwrap();
+        assert_eq!(
+            grid.render_with(|cell| if cell.is_some() { '#' } else { ' ' }),
+            "# \n #\n"
+        );
+    }
+}
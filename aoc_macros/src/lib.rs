@@ -0,0 +1,204 @@
+use proc_macro::TokenStream;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+const EXAMPLES_FILE: &str = "examples.toml";
+
+/// One example from a puzzle description, with the answers it is known to
+/// give. The input is either inline or a file in the crate.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Example {
+    name: String,
+    input: Option<String>,
+    file: Option<String>,
+    part_1: Option<toml::Value>,
+    part_2: Option<toml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Examples {
+    #[serde(default, rename = "example")]
+    examples: Vec<Example>,
+}
+
+/// Generates a `#[test]` for every answer of every example in the calling
+/// crate's `examples.toml`, checking it against the given `Solution`:
+///
+/// ```toml
+/// [[example]]
+/// name = "simple"
+/// file = "input_simple.txt"   # or: input = "125 17"
+/// part_1 = 143
+/// part_2 = 123
+/// ```
+///
+/// Use it inside the crate's test module, as `aoc_core::example_tests!(Day05);`.
+#[proc_macro]
+pub fn example_tests(input: TokenStream) -> TokenStream {
+    let solution = input.to_string();
+    let crate_name = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+
+    let code = match expand(&crate_name, &solution, Path::new(&manifest_dir)) {
+        Ok(code) => code,
+        Err(err) => format!("compile_error!({:?});", err),
+    };
+
+    code.parse().expect("Generated tests are valid Rust")
+}
+
+fn expand(crate_name: &str, solution: &str, manifest_dir: &Path) -> Result<String, String> {
+    if solution.trim().is_empty() {
+        return Err(String::from(
+            "Expected the solution type, e.g. example_tests!(Day01)",
+        ));
+    }
+
+    let path = manifest_dir.join(EXAMPLES_FILE);
+    let text = fs::read_to_string(&path)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+    let examples: Examples = toml::from_str(&text)
+        .map_err(|err| format!("Could not parse {}: {}", path.display(), err))?;
+
+    // Rebuild the tests whenever the examples change.
+    let mut code = format!(
+        "const _: &[u8] = include_bytes!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/{}\"));\n",
+        EXAMPLES_FILE
+    );
+
+    for example in &examples.examples {
+        let valid_name = !example.name.is_empty()
+            && example
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_name {
+            return Err(format!(
+                "Example name {:?} must be lowercase letters, digits and underscores",
+                example.name
+            ));
+        }
+
+        let input = match (&example.input, &example.file) {
+            (Some(input), None) => format!("{:?}", input),
+            (None, Some(file)) => {
+                if !manifest_dir.join(file).exists() {
+                    return Err(format!("Example {}: {} does not exist", example.name, file));
+                }
+                format!(
+                    "include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), {:?}))",
+                    format!("/{}", file)
+                )
+            }
+            _ => {
+                return Err(format!(
+                    "Example {} needs exactly one of input or file",
+                    example.name
+                ))
+            }
+        };
+
+        for (part, expected) in [(1, &example.part_1), (2, &example.part_2)] {
+            let Some(expected) = expected else {
+                continue;
+            };
+            let expected = match expected {
+                toml::Value::Integer(value) => value.to_string(),
+                toml::Value::String(value) => value.clone(),
+                other => {
+                    return Err(format!(
+                        "Example {}: part_{} must be an integer or a string, not {}",
+                        example.name,
+                        part,
+                        other.type_str()
+                    ))
+                }
+            };
+
+            code.push_str(&format!(
+                "#[test]\nfn {}_example_{}_part_{}() {{\n    \
+                 ::aoc_core::check_example::<{}>({:?}, {}, {}, {:?});\n}}\n",
+                crate_name, example.name, part, solution, example.name, input, part, expected
+            ));
+        }
+    }
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn examples_dir(name: &str, examples: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_macros_{}_{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(EXAMPLES_FILE), examples).unwrap();
+        fs::write(dir.join("input_simple.txt"), "3   4\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn macros_test_expand() {
+        let dir = examples_dir(
+            "expand",
+            r#"
+[[example]]
+name = "simple"
+file = "input_simple.txt"
+part_1 = 11
+part_2 = "not solved"
+
+[[example]]
+name = "inline"
+input = "1 2\n"
+part_2 = 3
+"#,
+        );
+
+        let code = expand("day_01", "Day01", &dir).unwrap();
+        assert!(code.contains("fn day_01_example_simple_part_1()"));
+        assert!(code.contains("\"simple\", include_str!"));
+        assert!(code.contains("1, \"11\");"));
+        assert!(code.contains("2, \"not solved\");"));
+        assert!(!code.contains("fn day_01_example_inline_part_1()"));
+        assert!(
+            code.contains("::aoc_core::check_example::<Day01>(\"inline\", \"1 2\\n\", 2, \"3\");")
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn macros_test_invalid_examples() {
+        let dir = examples_dir(
+            "invalid",
+            "[[example]]\nname = \"Bad Name\"\ninput = \"1\"\npart_1 = 1\n",
+        );
+        assert!(expand("day_01", "Day01", &dir)
+            .unwrap_err()
+            .contains("must be lowercase"));
+
+        fs::write(
+            dir.join(EXAMPLES_FILE),
+            "[[example]]\nname = \"missing\"\nfile = \"nope.txt\"\npart_1 = 1\n",
+        )
+        .unwrap();
+        assert!(expand("day_01", "Day01", &dir)
+            .unwrap_err()
+            .contains("nope.txt does not exist"));
+
+        fs::write(
+            dir.join(EXAMPLES_FILE),
+            "[[example]]\nname = \"neither\"\npart_1 = 1\n",
+        )
+        .unwrap();
+        assert!(expand("day_01", "Day01", &dir)
+            .unwrap_err()
+            .contains("exactly one of input or file"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
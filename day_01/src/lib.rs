@@ -1,91 +1,533 @@
-use std::error::Error;
-use std::fs;
+use aoc_core::{column_of, input_path, parse_field, read_input, Answer, AocError, Solution};
+use std::any::type_name;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::str::FromStr;
 
-pub fn find_distance(left: &mut [i32], right: &mut [i32]) -> Result<i32, Box<dyn Error>> {
+/// Integer types location IDs can be stored as, with the checked arithmetic
+/// the distance and similarity scores need.
+pub trait LocationId: Copy + Ord + Hash + FromStr + fmt::Display {
+    const ZERO: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn from_count(count: usize) -> Option<Self>;
+}
+
+macro_rules! impl_location_id {
+    ($($type:ty),*) => {
+        $(
+            impl LocationId for $type {
+                const ZERO: Self = 0;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$type>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$type>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$type>::checked_mul(self, other)
+                }
+
+                fn from_count(count: usize) -> Option<Self> {
+                    Self::try_from(count).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_location_id!(i32, i64, u32, u64, i128, u128);
+
+/// How to pair up two lists of different lengths. The policy applies to the
+/// lists as given, before any sorting.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Pairing<T> {
+    /// Lists of different lengths are an error.
+    #[default]
+    Strict,
+    /// Entries past the end of the shorter list are ignored.
+    Truncate,
+    /// The shorter list is padded with the given value.
+    Pad(T),
+}
+
+impl<T: Copy> Pairing<T> {
+    /// Both lists, brought to the same length.
+    pub fn apply(&self, left: &[T], right: &[T]) -> Result<(Vec<T>, Vec<T>), AocError> {
+        let mut left = left.to_vec();
+        let mut right = right.to_vec();
+
+        match *self {
+            _ if left.len() == right.len() => {}
+            Pairing::Strict => {
+                return Err(AocError::invalid("Vectors must be of the same length"));
+            }
+            Pairing::Truncate => {
+                let len = left.len().min(right.len());
+                left.truncate(len);
+                right.truncate(len);
+            }
+            Pairing::Pad(sentinel) => {
+                let len = left.len().max(right.len());
+                left.resize(len, sentinel);
+                right.resize(len, sentinel);
+            }
+        }
+
+        Ok((left, right))
+    }
+}
+
+fn overflow<T>(what: &str) -> AocError {
+    AocError::invalid(format!("{} overflows {}", what, type_name::<T>()))
+}
+
+pub fn find_distance(left: &[i32], right: &[i32]) -> Result<i32, AocError> {
     if left.len() != right.len() {
-        return Err("Vectors must be of the same length".into());
+        return Err(AocError::invalid("Vectors must be of the same length"));
     }
 
-    left.sort();
-    right.sort();
+    distance(left, right)
+}
 
-    let distance = left
-        .iter()
+/// Total distance between the lists once both are sorted. The inputs are
+/// left untouched; sorted copies are made instead.
+pub fn distance<T: LocationId>(left: &[T], right: &[T]) -> Result<T, AocError> {
+    distance_with(left, right, Pairing::Strict)
+}
+
+pub fn distance_with<T: LocationId>(
+    left: &[T],
+    right: &[T],
+    pairing: Pairing<T>,
+) -> Result<T, AocError> {
+    let (mut left, mut right) = pairing.apply(left, right)?;
+    left.sort_unstable();
+    right.sort_unstable();
+
+    sorted_distance(&left, &right)
+}
+
+fn sorted_distance<T: LocationId>(left: &[T], right: &[T]) -> Result<T, AocError> {
+    left.iter()
         .zip(right.iter())
-        .map(|(left, right)| (right - left).abs())
-        .sum();
+        .try_fold(T::ZERO, |total, (&left, &right)| {
+            total.checked_add(pair_distance(left, right)?)
+        })
+        .ok_or_else(|| overflow::<T>("Total distance"))
+}
 
-    Ok(distance)
+fn pair_distance<T: LocationId>(left: T, right: T) -> Option<T> {
+    left.max(right).checked_sub(left.min(right))
 }
 
-pub fn calculate_similarity(left: &[i32], right: &[i32]) -> Result<i32, Box<dyn Error>> {
-    if left.len() != right.len() {
-        return Err("Vectors must be of the same length".into());
-    }
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PairDistance<T> {
+    pub left: T,
+    pub right: T,
+    pub distance: T,
+}
+
+/// Every pair that makes up the total distance, in sorted order.
+#[derive(Debug)]
+pub struct DistanceReport<T> {
+    pub pairs: Vec<PairDistance<T>>,
+    pub total: T,
+}
+
+pub fn distance_report<T: LocationId>(
+    left: &[T],
+    right: &[T],
+    pairing: Pairing<T>,
+) -> Result<DistanceReport<T>, AocError> {
+    let (mut left, mut right) = pairing.apply(left, right)?;
+    left.sort_unstable();
+    right.sort_unstable();
 
-    let similarity = left
+    let pairs = left
+        .into_iter()
+        .zip(right)
+        .map(|(left, right)| {
+            let distance = pair_distance(left, right).ok_or_else(|| overflow::<T>("Distance"))?;
+            Ok(PairDistance {
+                left,
+                right,
+                distance,
+            })
+        })
+        .collect::<Result<Vec<_>, AocError>>()?;
+    let total = pairs
         .iter()
-        .map(|left| right.iter().filter(|right| *right == left).count() as i32 * *left)
-        .sum();
+        .try_fold(T::ZERO, |total, pair| total.checked_add(pair.distance))
+        .ok_or_else(|| overflow::<T>("Total distance"))?;
+
+    Ok(DistanceReport { pairs, total })
+}
+
+impl<T: LocationId> DistanceReport<T> {
+    /// The pair furthest apart; the first one if several are.
+    pub fn max(&self) -> Option<&PairDistance<T>> {
+        self.pairs.iter().rev().max_by_key(|pair| pair.distance)
+    }
+
+    /// Median distance. With an even number of pairs this is the lower of the
+    /// two middle distances.
+    pub fn median(&self) -> Option<T> {
+        let mut distances: Vec<T> = self.pairs.iter().map(|pair| pair.distance).collect();
+        if distances.is_empty() {
+            return None;
+        }
+
+        let middle = (distances.len() - 1) / 2;
+        Some(*distances.select_nth_unstable(middle).1)
+    }
+
+    /// How many pairs are at each distance.
+    pub fn histogram(&self) -> BTreeMap<T, usize> {
+        let mut histogram = BTreeMap::new();
+        for pair in &self.pairs {
+            *histogram.entry(pair.distance).or_default() += 1;
+        }
+
+        histogram
+    }
+
+    /// Writes the pairs as CSV with a `left,right,distance` header.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "left,right,distance")?;
+        for pair in &self.pairs {
+            writeln!(writer, "{},{},{}", pair.left, pair.right, pair.distance)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn calculate_similarity(left: &[i32], right: &[i32]) -> Result<i32, AocError> {
+    similarity(left, right)
+}
+
+pub fn similarity<T: LocationId>(left: &[T], right: &[T]) -> Result<T, AocError> {
+    similarity_with(left, right, Pairing::Strict)
+}
+
+pub fn similarity_with<T: LocationId>(
+    left: &[T],
+    right: &[T],
+    pairing: Pairing<T>,
+) -> Result<T, AocError> {
+    let (left, right) = pairing.apply(left, right)?;
+    similarity_from_iters(left, right)
+}
+
+/// Similarity score of two lists given as iterators. Only the counts of the
+/// right list are held in memory; the left list is streamed.
+pub fn similarity_from_iters<T: LocationId>(
+    left: impl IntoIterator<Item = T>,
+    right: impl IntoIterator<Item = T>,
+) -> Result<T, AocError> {
+    let mut counts: HashMap<T, usize> = HashMap::new();
+    let mut right_len = 0;
+    for number in right {
+        *counts.entry(number).or_default() += 1;
+        right_len += 1;
+    }
+
+    let mut left_len = 0;
+    let mut similarity = T::ZERO;
+    for number in left {
+        let count = counts.get(&number).copied().unwrap_or(0);
+        similarity = T::from_count(count)
+            .and_then(|count| number.checked_mul(count))
+            .and_then(|score| similarity.checked_add(score))
+            .ok_or_else(|| overflow::<T>("Similarity score"))?;
+        left_len += 1;
+    }
+
+    if left_len != right_len {
+        return Err(AocError::invalid("Vectors must be of the same length"));
+    }
 
     Ok(similarity)
 }
 
-pub fn load_text_vectors() -> Result<(Vec<i32>, Vec<i32>), Box<dyn Error>> {
+pub fn load_text_vectors() -> Result<(Vec<i32>, Vec<i32>), AocError> {
     let file_path = "input.txt";
 
-    let text = fs::read_to_string(file_path).expect("Could not read from file");
-    let mut left = Vec::new();
-    let mut right = Vec::new();
+    let text = read_input(input_path!(file_path))?;
+    parse_text_vectors(&text)
+}
 
-    for line in text.lines() {
-        let mut numbers = line.split_whitespace();
-        if let (Some(left_num), Some(right_num)) = (numbers.next(), numbers.next()) {
-            left.push(left_num.parse()?);
-            right.push(right_num.parse()?);
-        }
+pub fn parse_text_vectors(text: &str) -> Result<(Vec<i32>, Vec<i32>), AocError> {
+    parse_lists(text)
+}
+
+/// Parses the two location lists, failing on the first malformed line.
+pub fn parse_lists<T>(text: &str) -> Result<(Vec<T>, Vec<T>), AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut parsed = parse_columns(text, 2);
+    if !parsed.malformed.is_empty() {
+        return Err(parsed.malformed.swap_remove(0));
     }
 
+    let right = parsed.columns.pop().expect("Two columns were parsed");
+    let left = parsed.columns.pop().expect("Two columns were parsed");
+
     Ok((left, right))
 }
 
+/// Whitespace-separated columns of numbers, one vector per column.
+#[derive(Debug)]
+pub struct Columns<T> {
+    pub columns: Vec<Vec<T>>,
+    /// One error per line that did not hold exactly the expected number of
+    /// values. Those lines are left out of `columns`.
+    pub malformed: Vec<AocError>,
+}
+
+pub fn load_columns<T>(file_path: &str, columns: usize) -> Result<Columns<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let text = read_input(input_path!(file_path))?;
+    Ok(parse_columns(&text, columns))
+}
+
+/// Reads `columns` values from every non-blank line, reporting malformed lines
+/// rather than stopping at the first one.
+pub fn parse_columns<T>(text: &str, columns: usize) -> Columns<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut parsed = Columns {
+        columns: (0..columns).map(|_| Vec::new()).collect(),
+        malformed: Vec::new(),
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != columns {
+            let column = match fields.get(columns) {
+                Some(extra) => column_of(line, extra),
+                None => line.len() + 1,
+            };
+            parsed.malformed.push(AocError::parse(
+                i + 1,
+                column,
+                line,
+                format!("Expected {} values, found {}", columns, fields.len()),
+            ));
+            continue;
+        }
+
+        let values: Result<Vec<T>, AocError> = fields
+            .iter()
+            .map(|field| parse_field(i, line, field))
+            .collect();
+        match values {
+            Ok(values) => {
+                for (column, value) in parsed.columns.iter_mut().zip(values) {
+                    column.push(value);
+                }
+            }
+            Err(err) => parsed.malformed.push(err),
+        }
+    }
+
+    parsed
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = (Vec<i64>, Vec<i64>);
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_lists(input)
+    }
+
+    fn part_1((left, right): &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(distance(left, right)?.into())
+    }
+
+    fn part_2((left, right): &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(similarity(left, right)?.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn day_01_test_distance_simple() {
-        let mut left = vec![3, 4, 2, 1, 3, 3];
-        let mut right = vec![4, 3, 5, 3, 9, 3];
-        assert_eq!(find_distance(&mut left, &mut right).unwrap(), 11);
+        let left = vec![3, 4, 2, 1, 3, 3];
+        let right = vec![4, 3, 5, 3, 9, 3];
+        assert_eq!(find_distance(&left, &right).unwrap(), 11);
+        assert_eq!(left, [3, 4, 2, 1, 3, 3]);
+        assert_eq!(right, [4, 3, 5, 3, 9, 3]);
     }
 
     #[test]
     fn day_01_test_not_equal_lengths() {
-        let mut left = vec![3, 4, 2, 1, 3, 3];
-        let mut right = vec![4, 3, 5, 3];
-        assert!(find_distance(&mut left, &mut right).is_err());
+        let left = vec![3, 4, 2, 1, 3, 3];
+        let right = vec![4, 3, 5, 3];
+        assert!(find_distance(&left, &right).is_err());
+    }
+
+    #[test]
+    fn day_01_test_malformed_line() {
+        let err = parse_text_vectors("3   4\n4   x3\n").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 5,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn day_01_test_pairing() {
+        let left = [3, 4, 2, 1, 3, 3, 100];
+        let right = [4, 3, 5, 3, 9, 3];
+
+        assert!(distance_with(&left, &right, Pairing::Strict).is_err());
+        assert_eq!(distance_with(&left, &right, Pairing::Truncate).unwrap(), 11);
+        assert_eq!(distance_with(&left, &right, Pairing::Pad(0)).unwrap(), 95);
+        assert_eq!(
+            similarity_with(&left, &right, Pairing::Truncate).unwrap(),
+            31
+        );
+        assert_eq!(similarity_with(&right, &left, Pairing::Pad(3)).unwrap(), 40);
+    }
+
+    #[test]
+    fn day_01_test_distance_report() {
+        let left = [3, 4, 2, 1, 3, 3];
+        let right = [4, 3, 5, 3, 9, 3];
+        let report = distance_report(&left, &right, Pairing::Strict).unwrap();
+
+        assert_eq!(report.total, 11);
+        assert_eq!(report.pairs.len(), 6);
+        assert_eq!(
+            report.max(),
+            Some(&PairDistance {
+                left: 4,
+                right: 9,
+                distance: 5
+            })
+        );
+        assert_eq!(report.median(), Some(1));
+        assert_eq!(
+            report.histogram().into_iter().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 2), (2, 2), (5, 1)]
+        );
+
+        let mut csv = Vec::new();
+        report.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "left,right,distance\n1,3,2\n2,3,1\n3,3,0\n3,4,1\n3,5,2\n4,9,5\n"
+        );
+    }
+
+    #[test]
+    fn day_01_test_parse_columns() {
+        let parsed: Columns<u64> = parse_columns("1 2 3\n4 5\n\n6 7 8 9\n10 x 12\n13 14 15\n", 3);
+        assert_eq!(parsed.columns, vec![vec![1, 13], vec![2, 14], vec![3, 15]]);
+
+        let lines: Vec<(usize, usize)> = parsed
+            .malformed
+            .iter()
+            .map(|err| match err {
+                AocError::Parse { line, column, .. } => (*line, *column),
+                _ => panic!("Expected a parse error, got {}", err),
+            })
+            .collect();
+        assert_eq!(lines, vec![(2, 4), (4, 7), (5, 4)]);
+
+        let err = parse_text_vectors("3   4\n4\n").unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
     }
 
     #[test]
     fn day_01_test_distance_with_input() {
-        let (mut left, mut right) = load_text_vectors().unwrap();
-        assert_eq!(find_distance(&mut left, &mut right).unwrap(), 1941353);
+        let (left, right) = load_text_vectors().unwrap();
+        assert_eq!(find_distance(&left, &right).unwrap(), 1941353);
     }
 
     #[test]
     fn day_01_test_similarity_simple() {
-        let mut left = vec![3, 4, 2, 1, 3, 3];
-        let mut right = vec![4, 3, 5, 3, 9, 3];
-        assert_eq!(calculate_similarity(&mut left, &mut right).unwrap(), 31);
+        let left = vec![3, 4, 2, 1, 3, 3];
+        let right = vec![4, 3, 5, 3, 9, 3];
+        assert_eq!(calculate_similarity(&left, &right).unwrap(), 31);
+    }
+
+    #[test]
+    fn day_01_test_similarity_from_iters() {
+        let left = vec![3, 4, 2, 1, 3, 3];
+        let right = vec![4, 3, 5, 3, 9, 3];
+        assert_eq!(similarity_from_iters(left, right).unwrap(), 31);
+        assert!(similarity_from_iters([1, 2], [1]).is_err());
+    }
+
+    #[test]
+    fn day_01_test_similarity_large_lists() {
+        let left: Vec<i32> = (0..1_000_000).collect();
+        let right: Vec<i32> = (0..1_000_000).map(|i| i % 10).collect();
+
+        // Only the values below 10 appear in the right list, 100000 times each.
+        let expected = (0..10).sum::<i32>() * 100_000;
+        assert_eq!(calculate_similarity(&left, &right).unwrap(), expected);
+    }
+
+    #[test]
+    fn day_01_test_distance_generic() {
+        let left: Vec<u64> = vec![3, 4, 2, 1, 3, 3];
+        let right: Vec<u64> = vec![4, 3, 5, 3, 9, 3];
+        assert_eq!(distance(&left, &right).unwrap(), 11);
+        assert_eq!(left, vec![3, 4, 2, 1, 3, 3]);
+
+        let left: Vec<i128> = vec![-5_000_000_000_000, 7];
+        let right: Vec<i128> = vec![5_000_000_000_000, 7];
+        assert_eq!(distance(&left, &right).unwrap(), 10_000_000_000_000);
+    }
+
+    #[test]
+    fn day_01_test_overflow() {
+        assert!(distance(&[i64::MIN], &[i64::MAX]).is_err());
+        assert!(distance(&[0, 0], &[u64::MAX, 1]).is_err());
+        let big = u64::MAX / 2 + 1;
+        let err = similarity(&[big, 0, 0], &[big, big, 1]).unwrap_err();
+        assert_eq!(err.to_string(), "Similarity score overflows u64");
+        assert_eq!(similarity(&[2i64, 3], &[2, 2]).unwrap(), 4);
     }
 
     #[test]
     fn day_01_test_similarity_with_input() {
-        let (mut left, mut right) = load_text_vectors().unwrap();
-        assert_eq!(
-            calculate_similarity(&mut left, &mut right).unwrap(),
-            22539317
-        );
+        let (left, right) = load_text_vectors().unwrap();
+        assert_eq!(calculate_similarity(&left, &right).unwrap(), 22539317);
     }
+
+    aoc_core::example_tests!(Day01);
 }
@@ -1,62 +1,522 @@
-use std::error::Error;
-use std::fs;
+use aoc_core::{input_path, parse_fields, Answer, AocError, Solution};
+use rayon::prelude::*;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 
-pub fn is_safe(report: &[i32]) -> bool {
-    let safe_positive = [1, 2, 3];
-    let safe_negative = [-1, -2, -3];
+/// Which way the levels in a report have to move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+    /// Either way, as long as the whole report agrees.
+    Either,
+}
+
+/// What makes a report safe. The default is the puzzle's rule: levels all
+/// increase or all decrease, by 1 to 3 at each step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    /// Smallest allowed change between adjacent levels, ignoring sign.
+    pub min_step: i32,
+    /// Largest allowed change between adjacent levels, ignoring sign.
+    pub max_step: i32,
+    pub direction: Direction,
+    /// Whether adjacent levels may be equal. Plateaus don't count towards
+    /// either direction.
+    pub allow_plateaus: bool,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        SafetyPolicy {
+            min_step: 1,
+            max_step: 3,
+            direction: Direction::Either,
+            allow_plateaus: false,
+        }
+    }
+}
+
+pub fn is_safe(report: &[i32], policy: &SafetyPolicy) -> bool {
+    first_violation(report, policy).is_none()
+}
 
-    let differences: Vec<i32> = report
-        .windows(2)
-        .map(|window| window[1] - window[0])
-        .collect();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsafeReason {
+    StepTooLarge,
+    StepTooSmall,
+    ZeroStep,
+    /// The levels turned around after moving the other way.
+    DirectionChange,
+    /// The levels moved against the direction the policy requires.
+    WrongDirection,
+}
 
-    let is_safe_positive = differences.iter().all(|item| safe_positive.contains(item));
-    let is_safe_negative = differences.iter().all(|item| safe_negative.contains(item));
+impl fmt::Display for UnsafeReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            UnsafeReason::StepTooLarge => "step too large",
+            UnsafeReason::StepTooSmall => "step too small",
+            UnsafeReason::ZeroStep => "zero step",
+            UnsafeReason::DirectionChange => "direction change",
+            UnsafeReason::WrongDirection => "wrong direction",
+        };
 
-    is_safe_positive || is_safe_negative
+        write!(f, "{}", reason)
+    }
 }
 
-pub fn is_safe_with_dampener(report: &[i32]) -> bool {
-    match is_safe(report) {
-        true => true,
-        false => {
-            for i in 0..report.len() {
-                let mut temp_report = report.to_owned();
-                temp_report.remove(i);
-                if is_safe(&temp_report) {
-                    return true;
+/// The first pair of adjacent levels that breaks the policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the window, i.e. of the first of the two levels.
+    pub window: usize,
+    pub levels: (i32, i32),
+    pub reason: UnsafeReason,
+}
+
+pub fn first_violation(report: &[i32], policy: &SafetyPolicy) -> Option<Violation> {
+    let required = policy.direction != Direction::Either;
+    let mut direction = Some(policy.direction).filter(|_| required);
+
+    for (window, levels) in report.windows(2).enumerate() {
+        // Widened, as the step between extreme levels does not fit an i32.
+        let step = i64::from(levels[1]) - i64::from(levels[0]);
+
+        let reason = if step == 0 {
+            (!policy.allow_plateaus).then_some(UnsafeReason::ZeroStep)
+        } else if step.abs() > i64::from(policy.max_step) {
+            Some(UnsafeReason::StepTooLarge)
+        } else if step.abs() < i64::from(policy.min_step) {
+            Some(UnsafeReason::StepTooSmall)
+        } else {
+            let step_direction = match step > 0 {
+                true => Direction::Increasing,
+                false => Direction::Decreasing,
+            };
+
+            match direction {
+                Some(expected) if expected != step_direction && required => {
+                    Some(UnsafeReason::WrongDirection)
+                }
+                Some(expected) if expected != step_direction => Some(UnsafeReason::DirectionChange),
+                _ => {
+                    direction = Some(step_direction);
+                    None
                 }
             }
-            false
+        };
+
+        if let Some(reason) = reason {
+            return Some(Violation {
+                window,
+                levels: (levels[0], levels[1]),
+                reason,
+            });
+        }
+    }
+
+    None
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    /// Safe once the levels at these indices are removed.
+    SafeWithDampener(Vec<usize>),
+    Unsafe,
+}
+
+/// How one report fares, for triaging reports one by one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// 1-based line of the report in the input.
+    pub line: usize,
+    pub verdict: Verdict,
+    /// Where the report as given first breaks the policy, unless it is safe.
+    pub violation: Option<Violation>,
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: ", self.line)?;
+        match &self.verdict {
+            Verdict::Safe => write!(f, "safe")?,
+            Verdict::SafeWithDampener(removed) => {
+                write!(f, "safe after removing levels {:?}", removed)?
+            }
+            Verdict::Unsafe => write!(f, "unsafe")?,
+        }
+
+        if let Some(violation) = &self.violation {
+            write!(
+                f,
+                ", window {} ({} -> {}): {}",
+                violation.window, violation.levels.0, violation.levels.1, violation.reason
+            )?;
         }
+
+        Ok(())
+    }
+}
+
+/// Diagnoses one report, allowing the dampener to remove up to
+/// `max_removals` levels.
+pub fn diagnose(
+    line: usize,
+    report: &[i32],
+    policy: &SafetyPolicy,
+    max_removals: usize,
+) -> Diagnosis {
+    let violation = first_violation(report, policy);
+    let verdict = match violation {
+        None => Verdict::Safe,
+        Some(_) => match dampen(report, policy, max_removals) {
+            Some(removed) => Verdict::SafeWithDampener(removed),
+            None => Verdict::Unsafe,
+        },
+    };
+
+    Diagnosis {
+        line,
+        verdict,
+        violation,
     }
 }
 
-pub fn count_safe_rows(apply_dampener: bool) -> Result<i32, Box<dyn Error>> {
+pub fn diagnose_reports(
+    text: &str,
+    policy: &SafetyPolicy,
+    max_removals: usize,
+) -> Result<Vec<Diagnosis>, AocError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let report: Vec<i32> = parse_fields(i, line)?;
+            Ok(diagnose(i + 1, &report, policy, max_removals))
+        })
+        .collect()
+}
+
+pub fn is_safe_with_dampener(report: &[i32], policy: &SafetyPolicy) -> bool {
+    dampen(report, policy, 1).is_some()
+}
+
+impl SafetyPolicy {
+    /// Whether two adjacent levels may differ by `step` in a report moving in
+    /// `direction`, which must be `Increasing` or `Decreasing`.
+    fn allows_step(&self, step: i64, direction: Direction) -> bool {
+        match step {
+            0 => self.allow_plateaus,
+            _ if step > 0 && direction == Direction::Decreasing => false,
+            _ if step < 0 && direction == Direction::Increasing => false,
+            _ => (i64::from(self.min_step)..=i64::from(self.max_step)).contains(&step.abs()),
+        }
+    }
+}
+
+/// Indices of the fewest levels to remove, at most `max_removals`, for
+/// `report` to be safe under `policy`, or `None` if that is not enough. An
+/// already safe report needs no removals.
+pub fn dampen(report: &[i32], policy: &SafetyPolicy, max_removals: usize) -> Option<Vec<usize>> {
+    let directions = match policy.direction {
+        Direction::Either => vec![Direction::Increasing, Direction::Decreasing],
+        direction => vec![direction],
+    };
+
+    directions
+        .into_iter()
+        .filter_map(|direction| dampen_in(report, policy, direction, max_removals))
+        .min_by_key(|removed| removed.len())
+}
+
+/// Finds the longest run of kept levels that moves in `direction`. Only the
+/// last `max_removals + 1` levels can precede a kept one, so this takes
+/// O(n * max_removals) time.
+fn dampen_in(
+    report: &[i32],
+    policy: &SafetyPolicy,
+    direction: Direction,
+    max_removals: usize,
+) -> Option<Vec<usize>> {
+    let len = report.len();
+    if len == 0 {
+        return Some(Vec::new());
+    }
+
+    // removals[j] is the fewest levels removed before j when j is kept, and
+    // previous[j] the level kept before it.
+    let mut removals = vec![usize::MAX; len];
+    let mut previous = vec![None; len];
+
+    for j in 0..len {
+        if j <= max_removals {
+            removals[j] = j;
+        }
+
+        for i in j.saturating_sub(max_removals + 1)..j {
+            if removals[i] == usize::MAX {
+                continue;
+            }
+            let step = i64::from(report[j]) - i64::from(report[i]);
+            if !policy.allows_step(step, direction) {
+                continue;
+            }
+
+            let cost = removals[i] + (j - i - 1);
+            if cost < removals[j] {
+                removals[j] = cost;
+                previous[j] = Some(i);
+            }
+        }
+    }
+
+    let last = (0..len)
+        .filter(|&j| removals[j] != usize::MAX)
+        .min_by_key(|&j| removals[j] + (len - 1 - j))?;
+    if removals[last] + (len - 1 - last) > max_removals {
+        return None;
+    }
+
+    let mut kept = vec![false; len];
+    let mut level = Some(last);
+    while let Some(j) = level {
+        kept[j] = true;
+        level = previous[j];
+    }
+
+    Some((0..len).filter(|&i| !kept[i]).collect())
+}
+
+pub fn count_safe_rows(apply_dampener: bool) -> Result<i32, AocError> {
     let file_path = "input.txt";
 
-    let text = fs::read_to_string(file_path).expect("Could not read from file");
+    let path = input_path!(file_path);
+    let file = File::open(&path).map_err(|err| AocError::io(&path, err))?;
+    let tally = Evaluator::default().evaluate(BufReader::new(file))?;
+
+    let count_safe = match apply_dampener {
+        false => tally.safe,
+        true => tally.safe + tally.safe_with_dampener,
+    };
+
+    Ok(count_safe as i32)
+}
+
+/// What to do with lines that are not a list of numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MalformedLines {
+    /// Stop at the first one with its error.
+    #[default]
+    Fail,
+    /// Count them and carry on.
+    Skip,
+    /// Collect their errors and carry on.
+    Report,
+}
+
+/// Counts of safe and unsafe reports in a report log.
+#[derive(Debug, Default)]
+pub struct Tally {
+    pub safe: usize,
+    /// Reports that are only safe with the dampener.
+    pub safe_with_dampener: usize,
+    pub unsafe_reports: usize,
+    pub skipped: usize,
+    /// Errors for malformed lines, with `MalformedLines::Report`.
+    pub malformed: Vec<AocError>,
+}
+
+impl Tally {
+    fn add(
+        &mut self,
+        outcome: Result<Verdict, AocError>,
+        malformed: MalformedLines,
+    ) -> Result<(), AocError> {
+        match outcome {
+            Ok(Verdict::Safe) => self.safe += 1,
+            Ok(Verdict::SafeWithDampener(_)) => self.safe_with_dampener += 1,
+            Ok(Verdict::Unsafe) => self.unsafe_reports += 1,
+            Err(err) => match malformed {
+                MalformedLines::Fail => return Err(err),
+                MalformedLines::Skip => self.skipped += 1,
+                MalformedLines::Report => self.malformed.push(err),
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluates report logs line by line, so they never have to fit in memory.
+/// Blank lines are ignored.
+#[derive(Clone, Copy, Debug)]
+pub struct Evaluator {
+    pub policy: SafetyPolicy,
+    /// Levels the dampener may remove from each report.
+    pub max_removals: usize,
+    pub malformed: MalformedLines,
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Evaluator {
+            policy: SafetyPolicy::default(),
+            max_removals: 1,
+            malformed: MalformedLines::default(),
+        }
+    }
+}
+
+impl Evaluator {
+    pub fn evaluate(&self, mut reader: impl BufRead) -> Result<Tally, AocError> {
+        let mut tally = Tally::default();
+        let mut line = Vec::new();
+
+        for i in 0.. {
+            line.clear();
+            if read_line(&mut reader, &mut line)? == 0 {
+                break;
+            }
+
+            if let Some(outcome) = self.evaluate_line(i, &line) {
+                tally.add(outcome, self.malformed)?;
+            }
+        }
+
+        Ok(tally)
+    }
 
-    let count_safe = text
-        .lines()
-        .filter(|line| {
-            let line_slice: Vec<i32> = line
-                .split_whitespace()
-                .map(|num| num.parse().unwrap())
+    /// Like [`Evaluator::evaluate`], but reads `chunk_lines` lines at a time
+    /// and evaluates each chunk in parallel.
+    pub fn evaluate_parallel(
+        &self,
+        mut reader: impl BufRead,
+        chunk_lines: usize,
+    ) -> Result<Tally, AocError> {
+        let mut tally = Tally::default();
+        let mut chunk = Vec::new();
+        let mut first_line = 0;
+
+        loop {
+            chunk.clear();
+            while chunk.len() < chunk_lines.max(1) {
+                let mut line = Vec::new();
+                if read_line(&mut reader, &mut line)? == 0 {
+                    break;
+                }
+                chunk.push(line);
+            }
+            if chunk.is_empty() {
+                break;
+            }
+
+            let outcomes: Vec<_> = chunk
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, line)| self.evaluate_line(first_line + i, line))
                 .collect();
-            match apply_dampener {
-                false => is_safe(&line_slice),
-                true => is_safe_with_dampener(&line_slice),
+            for outcome in outcomes {
+                tally.add(outcome, self.malformed)?;
+            }
+
+            first_line += chunk.len();
+        }
+
+        Ok(tally)
+    }
+
+    /// `None` for blank lines. Lines that are not UTF-8 are malformed.
+    fn evaluate_line(&self, line_index: usize, line: &[u8]) -> Option<Result<Verdict, AocError>> {
+        let line = match std::str::from_utf8(line) {
+            Ok(line) => line.trim_end_matches(['\n', '\r']),
+            Err(err) => {
+                let text = String::from_utf8_lossy(line);
+                let valid = String::from_utf8_lossy(&line[..err.valid_up_to()]);
+                return Some(Err(AocError::parse(
+                    line_index + 1,
+                    valid.chars().count() + 1,
+                    text.trim_end_matches(['\n', '\r']),
+                    "Line is not valid UTF-8",
+                )));
             }
+        };
+        if line.trim().is_empty() {
+            return None;
+        }
+
+        let outcome = parse_fields(line_index, line).map(|report: Vec<i32>| {
+            diagnose(line_index + 1, &report, &self.policy, self.max_removals).verdict
+        });
+
+        Some(outcome)
+    }
+}
+
+/// Reads a line as bytes, so that only I/O failures are errors here and
+/// undecodable lines can go through the malformed line policy.
+fn read_line(reader: &mut impl BufRead, line: &mut Vec<u8>) -> Result<usize, AocError> {
+    reader
+        .read_until(b'\n', line)
+        .map_err(|err| AocError::io("<reader>", err))
+}
+
+pub fn count_safe_reports(text: &str, apply_dampener: bool) -> Result<i32, AocError> {
+    let policy = SafetyPolicy::default();
+    let count_safe = parse_reports(text)?
+        .iter()
+        .filter(|report| match apply_dampener {
+            false => is_safe(report, &policy),
+            true => is_safe_with_dampener(report, &policy),
         })
         .count() as i32;
 
     Ok(count_safe)
 }
 
+/// Parses one report per line, skipping blank lines like [`Evaluator`].
+pub fn parse_reports(text: &str) -> Result<Vec<Vec<i32>>, AocError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_fields(i, line))
+        .collect()
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_reports(input)
+    }
+
+    fn part_1(reports: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(reports
+            .iter()
+            .filter(|report| is_safe(report, &SafetyPolicy::default()))
+            .count()
+            .into())
+    }
+
+    fn part_2(reports: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(reports
+            .iter()
+            .filter(|report| is_safe_with_dampener(report, &SafetyPolicy::default()))
+            .count()
+            .into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_core::read_input;
 
     #[test]
     fn day_02_test_is_safe_simple() {
@@ -66,13 +526,14 @@ mod tests {
         let report4 = vec![1, 3, 2, 4, 5];
         let report5 = vec![8, 6, 4, 4, 1];
         let report6 = vec![1, 3, 6, 7, 9];
+        let policy = SafetyPolicy::default();
 
-        assert_eq!(is_safe(&report1), true);
-        assert_eq!(is_safe(&report2), false);
-        assert_eq!(is_safe(&report3), false);
-        assert_eq!(is_safe(&report4), false);
-        assert_eq!(is_safe(&report5), false);
-        assert_eq!(is_safe(&report6), true);
+        assert!(is_safe(&report1, &policy));
+        assert!(!is_safe(&report2, &policy));
+        assert!(!is_safe(&report3, &policy));
+        assert!(!is_safe(&report4, &policy));
+        assert!(!is_safe(&report5, &policy));
+        assert!(is_safe(&report6, &policy));
     }
 
     #[test]
@@ -88,17 +549,289 @@ mod tests {
         let report4 = vec![1, 3, 2, 4, 5];
         let report5 = vec![8, 6, 4, 4, 1];
         let report6 = vec![1, 3, 6, 7, 9];
+        let policy = SafetyPolicy::default();
+
+        assert!(is_safe_with_dampener(&report1, &policy));
+        assert!(!is_safe_with_dampener(&report2, &policy));
+        assert!(!is_safe_with_dampener(&report3, &policy));
+        assert!(is_safe_with_dampener(&report4, &policy));
+        assert!(is_safe_with_dampener(&report5, &policy));
+        assert!(is_safe_with_dampener(&report6, &policy));
+    }
+
+    #[test]
+    fn day_02_test_safety_policy() {
+        let increasing = SafetyPolicy {
+            direction: Direction::Increasing,
+            ..SafetyPolicy::default()
+        };
+        assert!(is_safe(&[1, 3, 6, 7, 9], &increasing));
+        assert!(!is_safe(&[7, 6, 4, 2, 1], &increasing));
+        assert!(is_safe_with_dampener(&[1, 3, 2, 4, 5], &increasing));
+
+        let plateaus = SafetyPolicy {
+            allow_plateaus: true,
+            ..SafetyPolicy::default()
+        };
+        assert!(is_safe(&[8, 6, 4, 4, 1], &plateaus));
+        assert!(!is_safe(&[1, 1, 2, 1], &plateaus));
+
+        let wide = SafetyPolicy {
+            min_step: 2,
+            max_step: 5,
+            ..SafetyPolicy::default()
+        };
+        assert!(is_safe(&[1, 3, 8, 10], &wide));
+        assert!(!is_safe(&[1, 2, 4], &wide));
+        assert!(is_safe_with_dampener(&[1, 2, 4], &wide));
+    }
+
+    #[test]
+    fn day_02_test_diagnose_reports() {
+        let text = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9\n";
+        let diagnoses = diagnose_reports(text, &SafetyPolicy::default(), 1).unwrap();
+
+        let summary: Vec<String> = diagnoses.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "Line 1: safe",
+                "Line 2: unsafe, window 1 (2 -> 7): step too large",
+                "Line 3: unsafe, window 2 (6 -> 2): step too large",
+                "Line 4: safe after removing levels [2], window 1 (3 -> 2): direction change",
+                "Line 5: safe after removing levels [3], window 2 (4 -> 4): zero step",
+                "Line 6: safe",
+            ]
+        );
+        assert_eq!(diagnoses[3].verdict, Verdict::SafeWithDampener(vec![2]));
+    }
+
+    #[test]
+    fn day_02_test_diagnose_reports_blank_lines() {
+        let text = "\n7 6 4 2 1\n\n1 2 7 8 9\n";
+        let lines: Vec<usize> = diagnose_reports(text, &SafetyPolicy::default(), 1)
+            .unwrap()
+            .iter()
+            .map(|diagnosis| diagnosis.line)
+            .collect();
+        assert_eq!(lines, vec![2, 4]);
+
+        let error =
+            diagnose_reports("\n7 6 4 2 1\n\n1 x 7\n", &SafetyPolicy::default(), 1).unwrap_err();
+        assert!(matches!(error, AocError::Parse { line: 4, .. }));
+    }
+
+    #[test]
+    fn day_02_test_first_violation() {
+        let increasing = SafetyPolicy {
+            direction: Direction::Increasing,
+            min_step: 2,
+            ..SafetyPolicy::default()
+        };
+
+        let violation = first_violation(&[5, 3, 1], &increasing).unwrap();
+        assert_eq!(
+            (violation.window, violation.reason),
+            (0, UnsafeReason::WrongDirection)
+        );
+        let violation = first_violation(&[1, 3, 4], &increasing).unwrap();
+        assert_eq!(
+            (violation.window, violation.reason),
+            (1, UnsafeReason::StepTooSmall)
+        );
+        assert_eq!(first_violation(&[1, 3, 5], &increasing), None);
+    }
+
+    #[test]
+    fn day_02_test_evaluate_streaming() {
+        let log = "7 6 4 2 1\n1 2 7 8 9\n\n9 7 6 x 1\n1 3 2 4 5\n8 6 4 4\n1 3 6 7 9";
+
+        let evaluator = Evaluator {
+            malformed: MalformedLines::Report,
+            ..Evaluator::default()
+        };
+        let tally = evaluator.evaluate(log.as_bytes()).unwrap();
+        assert_eq!(
+            (tally.safe, tally.safe_with_dampener, tally.unsafe_reports),
+            (2, 2, 1)
+        );
+        assert!(matches!(
+            tally.malformed[..],
+            [AocError::Parse { line: 4, .. }]
+        ));
+
+        let err = Evaluator::default().evaluate(log.as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 4,
+                column: 7,
+                ..
+            }
+        ));
 
-        assert_eq!(is_safe_with_dampener(&report1), true);
-        assert_eq!(is_safe_with_dampener(&report2), false);
-        assert_eq!(is_safe_with_dampener(&report3), false);
-        assert_eq!(is_safe_with_dampener(&report4), true);
-        assert_eq!(is_safe_with_dampener(&report5), true);
-        assert_eq!(is_safe_with_dampener(&report6), true);
+        let skipping = Evaluator {
+            malformed: MalformedLines::Skip,
+            ..Evaluator::default()
+        };
+        for chunk_lines in [1, 2, 100, usize::MAX] {
+            let tally = skipping
+                .evaluate_parallel(log.as_bytes(), chunk_lines)
+                .unwrap();
+            assert_eq!(
+                (tally.safe, tally.safe_with_dampener, tally.skipped),
+                (2, 2, 1)
+            );
+        }
+    }
+
+    #[test]
+    fn day_02_test_blank_lines_agree() {
+        let log = "\n7 6 4 2 1\n1 2 7 8 9\n\n   \n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9\n\n";
+
+        let tally = Evaluator::default().evaluate(log.as_bytes()).unwrap();
+        assert_eq!(parse_reports(log).unwrap().len(), 6);
+        assert_eq!(count_safe_reports(log, false).unwrap(), tally.safe as i32);
+        assert_eq!(
+            count_safe_reports(log, true).unwrap(),
+            (tally.safe + tally.safe_with_dampener) as i32
+        );
+        assert!(matches!(
+            parse_reports("1 2\n\nx\n"),
+            Err(AocError::Parse { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn day_02_test_evaluate_invalid_utf8() {
+        let log = b"7 6 4 2 1\n1 2 \xff 8 9\n1 3 6 7 9\n";
+
+        let skipping = Evaluator {
+            malformed: MalformedLines::Skip,
+            ..Evaluator::default()
+        };
+        let tally = skipping.evaluate(&log[..]).unwrap();
+        assert_eq!((tally.safe, tally.skipped), (2, 1));
+        let tally = skipping.evaluate_parallel(&log[..], 2).unwrap();
+        assert_eq!((tally.safe, tally.skipped), (2, 1));
+
+        let reporting = Evaluator {
+            malformed: MalformedLines::Report,
+            ..Evaluator::default()
+        };
+        let tally = reporting.evaluate(&log[..]).unwrap();
+        assert!(matches!(
+            tally.malformed[..],
+            [AocError::Parse {
+                line: 2,
+                column: 5,
+                ..
+            }]
+        ));
+
+        let err = Evaluator::default().evaluate(&log[..]).unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+    }
+
+    #[test]
+    fn day_02_test_evaluate_parallel_input() {
+        let text = read_input(input_path!("input.txt")).unwrap();
+        let tally = Evaluator::default()
+            .evaluate_parallel(text.as_bytes(), 64)
+            .unwrap();
+        assert_eq!(tally.safe, 502);
+        assert_eq!(tally.safe + tally.safe_with_dampener, 544);
+    }
+
+    #[test]
+    fn day_02_test_dampen() {
+        let policy = SafetyPolicy::default();
+
+        assert_eq!(dampen(&[7, 6, 4, 2, 1], &policy, 1), Some(vec![]));
+        assert_eq!(dampen(&[8, 6, 4, 4, 1], &policy, 1), Some(vec![3]));
+        assert_eq!(dampen(&[1, 2, 7, 8, 9], &policy, 1), None);
+        assert_eq!(dampen(&[1, 2, 7, 8, 9], &policy, 2), Some(vec![0, 1]));
+        assert_eq!(
+            dampen(&[9, 1, 2, 3, 20, 4, 5], &policy, 2),
+            Some(vec![0, 4])
+        );
+        assert_eq!(dampen(&[], &policy, 0), Some(vec![]));
+    }
+
+    #[test]
+    fn day_02_test_extreme_levels() {
+        let policy = SafetyPolicy::default();
+        let report = [i32::MIN, i32::MAX, i32::MAX - 1];
+
+        let violation = first_violation(&report, &policy).unwrap();
+        assert_eq!(violation.reason, UnsafeReason::StepTooLarge);
+        assert!(!is_safe(&[i32::MAX, i32::MIN], &policy));
+        assert_eq!(dampen(&report, &policy, 1), Some(vec![0]));
+        assert_eq!(dampen(&[0, i32::MIN, i32::MAX, 1], &policy, 1), None);
+
+        let wide = SafetyPolicy {
+            max_step: i32::MAX,
+            ..policy
+        };
+        assert!(is_safe(&[i32::MIN, -1, i32::MAX - 1], &wide));
+    }
+
+    /// Tries every way of removing up to `max_removals` levels.
+    fn dampen_brute_force(report: &[i32], policy: &SafetyPolicy, max_removals: usize) -> bool {
+        (0..1usize << report.len())
+            .filter(|mask| mask.count_ones() as usize <= max_removals)
+            .any(|mask| {
+                let kept: Vec<i32> = (0..report.len())
+                    .filter(|i| mask & (1 << i) == 0)
+                    .map(|i| report[i])
+                    .collect();
+                is_safe(&kept, policy)
+            })
+    }
+
+    #[test]
+    fn day_02_test_dampen_matches_brute_force() {
+        let policies = [
+            SafetyPolicy::default(),
+            SafetyPolicy {
+                direction: Direction::Decreasing,
+                allow_plateaus: true,
+                ..SafetyPolicy::default()
+            },
+        ];
+
+        for policy in &policies {
+            // Every report of six levels from 0, 2, .., 8.
+            for code in 0..5i32.pow(6) {
+                let report: Vec<i32> = (0..6).map(|i| code / 5i32.pow(i) % 5 * 2).collect();
+
+                for max_removals in 0..3 {
+                    let removed = dampen(&report, policy, max_removals);
+                    assert_eq!(
+                        removed.is_some(),
+                        dampen_brute_force(&report, policy, max_removals),
+                        "{:?} with {} removals",
+                        report,
+                        max_removals
+                    );
+
+                    if let Some(removed) = removed {
+                        assert!(removed.len() <= max_removals);
+                        let kept: Vec<i32> = (0..report.len())
+                            .filter(|i| !removed.contains(i))
+                            .map(|i| report[i])
+                            .collect();
+                        assert!(is_safe(&kept, policy));
+                    }
+                }
+            }
+        }
     }
 
     #[test]
     fn day_02_test_is_safe_input_with_dampener() {
         assert_eq!(count_safe_rows(true).unwrap(), 544);
     }
+
+    aoc_core::example_tests!(Day02);
 }
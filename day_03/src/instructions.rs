@@ -0,0 +1,412 @@
+use crate::trace::{Step, Trace};
+use aoc_core::AocError;
+use std::ops::RangeInclusive;
+
+/// Operands are capped at this many digits so that each fits an `i64`. What
+/// instructions compute from them is checked for overflow.
+const MAX_DIGITS: usize = 18;
+
+/// What an instruction does when the scanner reaches it.
+#[derive(Clone, Copy, Debug)]
+pub enum Effect {
+    /// Adds the value computed from the operands to the total, if enabled.
+    /// The function returns `None` if the value overflows.
+    Value(fn(&[i64]) -> Option<i64>),
+    Enable,
+    Disable,
+    /// Saves whether instructions are enabled, to be restored by `EndScope`.
+    BeginScope,
+    /// Restores the state saved by the matching `BeginScope`. Ignored when no
+    /// scope is open.
+    EndScope,
+}
+
+/// An instruction of the form `name(1,23,456)`: a name, then between
+/// `arity.start()` and `arity.end()` comma separated operands of `digits`
+/// ASCII digits each, in parentheses.
+#[derive(Clone, Debug)]
+pub struct Instruction {
+    pub name: String,
+    pub arity: RangeInclusive<usize>,
+    pub digits: RangeInclusive<usize>,
+    pub effect: Effect,
+}
+
+impl Instruction {
+    /// An instruction contributing `value(operands)` to the total, with the
+    /// puzzle's limit of one to three digits per operand.
+    pub fn value(
+        name: &str,
+        arity: RangeInclusive<usize>,
+        value: fn(&[i64]) -> Option<i64>,
+    ) -> Self {
+        Instruction {
+            name: name.to_string(),
+            arity,
+            digits: 1..=3,
+            effect: Effect::Value(value),
+        }
+    }
+
+    /// An instruction without operands that changes which instructions are
+    /// enabled.
+    pub fn control(name: &str, effect: Effect) -> Self {
+        Instruction {
+            name: name.to_string(),
+            arity: 0..=0,
+            digits: 1..=3,
+            effect,
+        }
+    }
+
+    /// The operands of this instruction if it starts at `start`, with the
+    /// position after it.
+    fn match_at(&self, text: &[u8], start: usize) -> Option<(Vec<i64>, usize)> {
+        let rest = &text[start..];
+        if !rest.starts_with(self.name.as_bytes()) || rest.get(self.name.len()) != Some(&b'(') {
+            return None;
+        }
+
+        let mut position = start + self.name.len() + 1;
+        let mut operands = Vec::new();
+
+        while operands.len() < *self.arity.end() {
+            // Operands after the first follow a comma, which is only part of
+            // the instruction if an operand does follow it.
+            let separator = usize::from(!operands.is_empty());
+            if separator == 1 && text.get(position) != Some(&b',') {
+                break;
+            }
+
+            let Some((operand, next)) = self.operand_at(text, position + separator) else {
+                break;
+            };
+            operands.push(operand);
+            position = next;
+        }
+
+        if operands.len() < *self.arity.start() || text.get(position) != Some(&b')') {
+            return None;
+        }
+
+        Some((operands, position + 1))
+    }
+
+    /// An operand with an allowed number of digits starting at `start`.
+    fn operand_at(&self, text: &[u8], start: usize) -> Option<(i64, usize)> {
+        let digits = text[start..]
+            .iter()
+            .take(self.digits.end() + 1)
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        if !self.digits.contains(&digits) {
+            return None;
+        }
+
+        let operand = text[start..start + digits]
+            .iter()
+            .fold(0, |operand, digit| operand * 10 + (digit - b'0') as i64);
+
+        Some((operand, start + digits))
+    }
+}
+
+/// An instruction found by [`InstructionSet::scan`], with its operands and
+/// the byte range `start..end` it was read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the instruction in the order it was registered.
+    pub instruction: usize,
+    pub operands: Vec<i64>,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The instructions the scanner recognises. Instructions are matched at the
+/// earliest position any of them starts; when several start at the same
+/// position, the first registered wins.
+#[derive(Clone, Debug, Default)]
+pub struct InstructionSet {
+    instructions: Vec<Instruction>,
+}
+
+impl InstructionSet {
+    pub fn new() -> Self {
+        InstructionSet::default()
+    }
+
+    /// The puzzle's `mul`, `do()` and `don't()`.
+    pub fn standard() -> Self {
+        let mut set = InstructionSet::new();
+        set.register(Instruction::value("mul", 2..=2, |operands| {
+            operands[0].checked_mul(operands[1])
+        }))
+        .and_then(|set| set.register(Instruction::control("do", Effect::Enable)))
+        .and_then(|set| set.register(Instruction::control("don't", Effect::Disable)))
+        .expect("Standard instructions are valid");
+        set
+    }
+
+    pub fn register(&mut self, instruction: Instruction) -> Result<&mut Self, AocError> {
+        let name = &instruction.name;
+        if name.is_empty() || !name.is_ascii() || name.contains(['(', ',', ')']) {
+            return Err(AocError::invalid(format!(
+                "Instruction name {:?} must be ASCII without parentheses or commas",
+                name
+            )));
+        }
+        if self.instructions.iter().any(|other| other.name == *name) {
+            return Err(AocError::invalid(format!(
+                "Instruction {} is already registered",
+                name
+            )));
+        }
+        if instruction.arity.is_empty() {
+            return Err(AocError::invalid(format!(
+                "Instruction {} has no valid number of operands",
+                name
+            )));
+        }
+        if !matches!(instruction.effect, Effect::Value(_)) && *instruction.arity.end() > 0 {
+            return Err(AocError::invalid(format!(
+                "Instruction {} controls enabling, so it cannot take operands",
+                name
+            )));
+        }
+        if instruction.digits.is_empty()
+            || *instruction.digits.start() == 0
+            || *instruction.digits.end() > MAX_DIGITS
+        {
+            return Err(AocError::invalid(format!(
+                "Instruction {} operands must have between 1 and {} digits",
+                name, MAX_DIGITS
+            )));
+        }
+
+        self.instructions.push(instruction);
+        Ok(self)
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Every instruction in `text`, skipping the garbage between them.
+    pub fn scan<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let text = text.as_bytes();
+        let mut position = 0;
+
+        std::iter::from_fn(move || {
+            while position < text.len() {
+                let start = position;
+                let found = self
+                    .instructions
+                    .iter()
+                    .enumerate()
+                    .find_map(|(i, instruction)| Some((i, instruction.match_at(text, start)?)));
+
+                match found {
+                    Some((instruction, (operands, end))) => {
+                        position = end;
+                        return Some(Match {
+                            instruction,
+                            operands,
+                            start,
+                            end,
+                        });
+                    }
+                    None => position += 1,
+                }
+            }
+
+            None
+        })
+    }
+
+    /// Sums the values of the enabled instructions in `text`, failing if a
+    /// value or the sum overflows.
+    pub fn evaluate(&self, text: &str) -> Result<i64, AocError> {
+        self.trace(text)?.total()
+    }
+
+    /// Every instruction in `text`, with whether it was enabled and what it
+    /// added to the total. Fails if a value overflows.
+    pub fn trace(&self, text: &str) -> Result<Trace, AocError> {
+        let mut enabled = true;
+        let mut scopes = Vec::new();
+        let mut steps = Vec::new();
+
+        for found in self.scan(text) {
+            let reached_enabled = enabled;
+            let mut contribution = None;
+            let instruction = &self.instructions[found.instruction];
+            match instruction.effect {
+                Effect::Value(value) if enabled => {
+                    let value = value(&found.operands).ok_or_else(|| {
+                        AocError::invalid(format!(
+                            "{} at byte {} overflows",
+                            instruction.name, found.start
+                        ))
+                    })?;
+                    contribution = Some(value);
+                }
+                Effect::Value(_) => contribution = Some(0),
+                Effect::Enable => enabled = true,
+                Effect::Disable => enabled = false,
+                Effect::BeginScope => scopes.push(enabled),
+                Effect::EndScope => enabled = scopes.pop().unwrap_or(enabled),
+            }
+
+            steps.push(Step {
+                start: found.start,
+                end: found.end,
+                enabled: reached_enabled,
+                contribution,
+            });
+        }
+
+        Ok(Trace {
+            steps,
+            enabled_at_end: enabled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_03_test_instruction_set_standard() {
+        let set = InstructionSet::standard();
+        let text = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(set.evaluate(text).unwrap(), 48);
+
+        let matches: Vec<(usize, Vec<i64>)> = set
+            .scan(text)
+            .map(|found| (found.instruction, found.operands))
+            .collect();
+        assert_eq!(
+            matches,
+            vec![
+                (0, vec![2, 4]),
+                (2, vec![]),
+                (0, vec![5, 5]),
+                (0, vec![11, 8]),
+                (1, vec![]),
+                (0, vec![8, 5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn day_03_test_instruction_set_custom() {
+        let mut set = InstructionSet::standard();
+        set.register(Instruction::value("add", 2..=2, |operands| {
+            operands[0].checked_add(operands[1])
+        }))
+        .unwrap()
+        .register(Instruction::value("sub", 2..=2, |operands| {
+            operands[0].checked_sub(operands[1])
+        }))
+        .unwrap()
+        .register(Instruction {
+            digits: 1..=6,
+            ..Instruction::value("sum", 1..=4, |operands| {
+                operands
+                    .iter()
+                    .try_fold(0i64, |sum, &operand| sum.checked_add(operand))
+            })
+        })
+        .unwrap()
+        .register(Instruction::value("any", 0..=4, |operands| {
+            Some(operands.len() as i64)
+        }))
+        .unwrap()
+        .register(Instruction::control("begin", Effect::BeginScope))
+        .unwrap()
+        .register(Instruction::control("end", Effect::EndScope))
+        .unwrap();
+
+        assert_eq!(
+            set.evaluate("add(2,3)sub(1,10)mul(2,2)").unwrap(),
+            5 - 9 + 4
+        );
+        assert_eq!(
+            set.evaluate("sum(1)sum(1,2,3,4)sum(1,2,3,4,5)sum()")
+                .unwrap(),
+            11
+        );
+        assert_eq!(
+            set.evaluate("sum(123456)sum(1234567)mul(1234,1)").unwrap(),
+            123456
+        );
+        assert_eq!(set.evaluate("sum(1,)sum(,1)add(1,2,)").unwrap(), 0);
+        assert_eq!(set.evaluate("any()any(1,2)any(7)any(1,2,3,4)").unwrap(), 7);
+        assert_eq!(set.evaluate("any(1,2,3,4,5)any(1,)any(x)").unwrap(), 0);
+        assert_eq!(
+            set.evaluate("begin()don't()add(1,1)begin()do()add(2,2)end()add(3,3)end()add(4,4)")
+                .unwrap(),
+            4 + 8
+        );
+        assert_eq!(set.evaluate("don't()end()add(1,1)").unwrap(), 0);
+    }
+
+    #[test]
+    fn day_03_test_instruction_set_overflow() {
+        let mut set = InstructionSet::standard();
+        set.register(Instruction {
+            digits: 1..=18,
+            ..Instruction::value("big", 2..=2, |operands| {
+                operands[0].checked_mul(operands[1])
+            })
+        })
+        .unwrap()
+        .register(Instruction {
+            digits: 1..=18,
+            ..Instruction::value("id", 1..=1, |operands| Some(operands[0]))
+        })
+        .unwrap();
+
+        assert_eq!(
+            set.evaluate("big(999999999,999999999)").unwrap(),
+            999999998000000001
+        );
+        assert!(set.evaluate("mul(1,1)big(999999999999999999,10)").is_err());
+        assert!(set.evaluate("don't()big(999999999999999999,10)").is_ok());
+        assert!(set.evaluate(&"id(999999999999999999)".repeat(9)).is_ok());
+        assert!(set.evaluate(&"id(999999999999999999)".repeat(10)).is_err());
+    }
+
+    #[test]
+    fn day_03_test_instruction_set_invalid() {
+        let mut set = InstructionSet::standard();
+        assert!(set
+            .register(Instruction::control("do", Effect::Enable))
+            .is_err());
+        assert!(set
+            .register(Instruction::value("a(b", 1..=1, |operands| Some(
+                operands[0]
+            )))
+            .is_err());
+        assert!(set
+            .register(Instruction {
+                digits: 0..=3,
+                ..Instruction::value("neg", 1..=1, |operands| operands[0].checked_neg())
+            })
+            .is_err());
+        assert!(set
+            .register(Instruction {
+                digits: 1..=19,
+                ..Instruction::value("neg", 1..=1, |operands| operands[0].checked_neg())
+            })
+            .is_err());
+        assert!(set
+            .register(Instruction {
+                arity: 1..=1,
+                ..Instruction::control("skip", Effect::Disable)
+            })
+            .is_err());
+        assert_eq!(set.instructions().len(), 3);
+    }
+}
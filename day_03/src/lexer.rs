@@ -0,0 +1,212 @@
+use crate::trace::{Step, Trace};
+
+/// The longest instruction, `mul(123,456)`.
+pub(crate) const MAX_INSTRUCTION_LEN: usize = 12;
+
+/// What a stretch of corrupted memory turned out to be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Mul(usize, usize),
+    Do,
+    Dont,
+    /// Anything that is not a valid instruction.
+    Garbage,
+}
+
+/// A token and the byte range `start..end` of the text it was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Splits corrupted memory into instructions and the garbage between them in
+/// a single pass. Instructions are matched at the earliest position they
+/// start, the way the regex versions match them.
+pub struct Lexer<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Lexer {
+            text: text.as_bytes(),
+            position: 0,
+        }
+    }
+
+    /// A lexer over raw bytes, which need not be valid UTF-8.
+    pub(crate) fn from_bytes(text: &'a [u8]) -> Self {
+        Lexer { text, position: 0 }
+    }
+
+    /// The instruction starting at `start`, with the position after it.
+    pub(crate) fn instruction_at(&self, start: usize) -> Option<(TokenKind, usize)> {
+        let rest = &self.text[start..];
+
+        if rest.starts_with(b"do()") {
+            return Some((TokenKind::Do, start + 4));
+        }
+        if rest.starts_with(b"don't()") {
+            return Some((TokenKind::Dont, start + 7));
+        }
+        if rest.starts_with(b"mul(") {
+            let (a, position) = self.number_at(start + 4)?;
+            if self.text.get(position) != Some(&b',') {
+                return None;
+            }
+            let (b, position) = self.number_at(position + 1)?;
+            if self.text.get(position) != Some(&b')') {
+                return None;
+            }
+            return Some((TokenKind::Mul(a, b), position + 1));
+        }
+
+        None
+    }
+
+    /// A number of one to three digits starting at `start`.
+    fn number_at(&self, start: usize) -> Option<(usize, usize)> {
+        let digits = self.text[start..]
+            .iter()
+            .take(4)
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        if !(1..=3).contains(&digits) {
+            return None;
+        }
+
+        let number = self.text[start..start + digits]
+            .iter()
+            .fold(0, |number, digit| number * 10 + (digit - b'0') as usize);
+
+        Some((number, start + digits))
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let start = self.position;
+        if start >= self.text.len() {
+            return None;
+        }
+
+        if let Some((kind, end)) = self.instruction_at(start) {
+            self.position = end;
+            return Some(Token { kind, start, end });
+        }
+
+        let mut end = start + 1;
+        while end < self.text.len() && self.instruction_at(end).is_none() {
+            end += 1;
+        }
+        self.position = end;
+
+        Some(Token {
+            kind: TokenKind::Garbage,
+            start,
+            end,
+        })
+    }
+}
+
+/// Sums the products of the `mul` instructions. When `conditional`, `do()`
+/// and `don't()` switch the instructions after them on and off.
+pub fn interpret(tokens: impl IntoIterator<Item = Token>, conditional: bool) -> usize {
+    let mut enabled = true;
+    let mut sum = 0;
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::Mul(a, b) if enabled => sum += a * b,
+            TokenKind::Do if conditional => enabled = true,
+            TokenKind::Dont if conditional => enabled = false,
+            _ => {}
+        }
+    }
+
+    sum
+}
+
+/// Like [`interpret`] with `conditional`, but records every instruction.
+pub fn trace(tokens: impl IntoIterator<Item = Token>) -> Trace {
+    let mut enabled = true;
+    let mut steps = Vec::new();
+
+    for token in tokens {
+        let contribution = match token.kind {
+            TokenKind::Mul(a, b) => Some(if enabled { (a * b) as i64 } else { 0 }),
+            TokenKind::Do | TokenKind::Dont => None,
+            TokenKind::Garbage => continue,
+        };
+
+        steps.push(Step {
+            start: token.start,
+            end: token.end,
+            enabled,
+            contribution,
+        });
+
+        match token.kind {
+            TokenKind::Do => enabled = true,
+            TokenKind::Dont => enabled = false,
+            _ => {}
+        }
+    }
+
+    Trace {
+        steps,
+        enabled_at_end: enabled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_03_lexer_tokens() {
+        let text = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let tokens: Vec<(TokenKind, &str)> = Lexer::new(text)
+            .map(|token| (token.kind, &text[token.start..token.end]))
+            .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                (TokenKind::Garbage, "x"),
+                (TokenKind::Mul(2, 4), "mul(2,4)"),
+                (TokenKind::Garbage, "&mul[3,7]!^"),
+                (TokenKind::Dont, "don't()"),
+                (TokenKind::Garbage, "_"),
+                (TokenKind::Mul(5, 5), "mul(5,5)"),
+                (TokenKind::Garbage, "+mul(32,64]("),
+                (TokenKind::Mul(11, 8), "mul(11,8)"),
+                (TokenKind::Garbage, "un"),
+                (TokenKind::Do, "do()"),
+                (TokenKind::Garbage, "?"),
+                (TokenKind::Mul(8, 5), "mul(8,5)"),
+                (TokenKind::Garbage, ")"),
+            ]
+        );
+    }
+
+    #[test]
+    fn day_03_lexer_edge_cases() {
+        let kinds = |text| Lexer::new(text).map(|token| token.kind).collect::<Vec<_>>();
+
+        assert_eq!(kinds("mul(1234,5)"), vec![TokenKind::Garbage]);
+        assert_eq!(kinds("mul(123,4)"), vec![TokenKind::Mul(123, 4)]);
+        assert_eq!(
+            kinds("mulmul(1,2)"),
+            vec![TokenKind::Garbage, TokenKind::Mul(1, 2)]
+        );
+        assert_eq!(kinds("mul(1,2"), vec![TokenKind::Garbage]);
+        assert_eq!(kinds("é do()"), vec![TokenKind::Garbage, TokenKind::Do]);
+        assert!(kinds("").is_empty());
+    }
+}
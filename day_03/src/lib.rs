@@ -1,14 +1,32 @@
+mod instructions;
+mod lexer;
+mod stream;
+mod trace;
+
+pub use instructions::{Effect, Instruction, InstructionSet, Match};
+pub use lexer::{interpret, trace, Lexer, Token, TokenKind};
+pub use stream::StreamScanner;
+pub use trace::{Step, Trace};
+
+use aoc_core::{input_path, read_input, Answer, AocError, Solution};
 use regex::Regex;
-use std::fs;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static MUL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"mul\((?<a>\d{1,3}),(?<b>\d{1,3})\)").unwrap());
+static DO: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"do\(\)").unwrap());
+static DONT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"don't\(\)").unwrap());
+static INSTRUCTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(do\(\)|don't\(\))|mul\((\d{1,3}),(\d{1,3})\)").unwrap());
 
-pub fn load_input() -> String {
+pub fn load_input() -> Result<String, AocError> {
     let file_path = "input.txt";
-    fs::read_to_string(file_path).expect("Could not read from file")
+    read_input(input_path!(file_path))
 }
 
 pub fn parse_text_with_regex(text: &str) -> usize {
-    let re = Regex::new(r"mul\((?<a>\d{1,3}),(?<b>\d{1,3})\)").unwrap();
-    re.captures_iter(text)
+    MUL.captures_iter(text)
         .map(|slice| {
             let a: usize = slice.name("a").unwrap().as_str().parse().unwrap();
             let b: usize = slice.name("b").unwrap().as_str().parse().unwrap();
@@ -18,24 +36,27 @@ pub fn parse_text_with_regex(text: &str) -> usize {
 }
 
 pub fn parse_text_conditional_with_regex(text: &str) -> usize {
-    // Regular expressions for enable / disable flags and multiply commands
-    let re_enable = Regex::new(r"do\(\)").unwrap();
-    let re_disable = Regex::new(r"don't\(\)").unwrap();
+    enabled_slices(text)
+        .into_iter()
+        .map(|slice| parse_text_with_regex(&text[slice]))
+        .sum()
+}
 
-    // Create iterators for all regex matches
-    let enable_iter = re_enable.find_iter(text);
-    let mut disable_iter = re_disable.find_iter(text);
+/// The stretches of `text` that [`parse_text_conditional_with_regex`] sums.
+fn enabled_slices(text: &str) -> Vec<Range<usize>> {
+    // Create iterators for all enable / disable flag matches
+    let enable_iter = DO.find_iter(text);
+    let mut disable_iter = DONT.find_iter(text);
 
-    // Initialize sum
-    let mut sum = 0;
+    // Initialize slices
+    let mut slices = Vec::new();
     let mut slice_start = 0;
     let mut slice_end = 0;
 
     // First iteration
     if let Some(disable_match) = disable_iter.next() {
         slice_end = disable_match.start();
-        let text_slice = &text[slice_start..slice_end];
-        sum += parse_text_with_regex(text_slice);
+        slices.push(slice_start..slice_end);
     }
 
     // Iterate until all enable conditionals have been used, starting
@@ -54,28 +75,24 @@ pub fn parse_text_conditional_with_regex(text: &str) -> usize {
         for disable_match in disable_iter.by_ref() {
             if disable_match.start() > slice_start {
                 slice_end = disable_match.start();
-                let text_slice = &text[slice_start..slice_end];
-                sum += parse_text_with_regex(text_slice);
+                slices.push(slice_start..slice_end);
                 continue 'enable_iter;
             }
         }
 
         // If disable iter used up, use remaining slice and end the while loop
-        let text_slice = &text[slice_start..];
-        sum += parse_text_with_regex(text_slice);
+        slices.push(slice_start..text.len());
         break;
     }
 
-    sum
+    slices
 }
 
 pub fn parse_text_conditional_with_regex_new(text: &str) -> usize {
-    let re = Regex::new(r"(do\(\)|don't\(\))|mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-
     let mut enabled = true;
     let mut sum = 0;
 
-    for capture in re.captures_iter(text) {
+    for capture in INSTRUCTION.captures_iter(text) {
         if let Some(flag) = capture.get(1) {
             match flag.as_str() {
                 "do()" => enabled = true,
@@ -94,6 +111,112 @@ pub fn parse_text_conditional_with_regex_new(text: &str) -> usize {
     sum
 }
 
+/// Like [`parse_text_conditional_with_regex`], but records every instruction,
+/// to compare against the other implementations. An instruction counts as
+/// enabled when it lies in one of the slices that function sums, and a
+/// `do()` or `don't()` when it is in or just ends such a slice.
+pub fn trace_conditional_with_regex(text: &str) -> Trace {
+    let slices = enabled_slices(text);
+    let in_slice = |start: usize, end: usize| {
+        slices
+            .iter()
+            .any(|slice| slice.start <= start && end <= slice.end)
+    };
+    let mut steps = Vec::new();
+
+    for capture in INSTRUCTION.captures_iter(text) {
+        let instruction = capture.get(0).unwrap();
+        let (start, end) = (instruction.start(), instruction.end());
+
+        let step = match (capture.get(2), capture.get(3)) {
+            (Some(a), Some(b)) => {
+                let enabled = in_slice(start, end);
+                let a: i64 = a.as_str().parse().unwrap();
+                let b: i64 = b.as_str().parse().unwrap();
+                Step {
+                    start,
+                    end,
+                    enabled,
+                    contribution: Some(if enabled { a * b } else { 0 }),
+                }
+            }
+            _ => Step {
+                start,
+                end,
+                enabled: in_slice(start, start),
+                contribution: None,
+            },
+        };
+        steps.push(step);
+    }
+
+    Trace {
+        steps,
+        enabled_at_end: in_slice(text.len(), text.len()),
+    }
+}
+
+/// Like [`parse_text_conditional_with_regex_new`], but records every
+/// instruction, to compare against the other implementations.
+pub fn trace_conditional_with_regex_new(text: &str) -> Trace {
+    let mut enabled = true;
+    let mut steps = Vec::new();
+
+    for capture in INSTRUCTION.captures_iter(text) {
+        let instruction = capture.get(0).unwrap();
+        let mut contribution = None;
+
+        if let Some(flag) = capture.get(1) {
+            steps.push(Step {
+                start: instruction.start(),
+                end: instruction.end(),
+                enabled,
+                contribution,
+            });
+            enabled = flag.as_str() == "do()";
+            continue;
+        }
+
+        if let (Some(a), Some(b)) = (capture.get(2), capture.get(3)) {
+            let a: i64 = a.as_str().parse().unwrap();
+            let b: i64 = b.as_str().parse().unwrap();
+            contribution = Some(if enabled { a * b } else { 0 });
+        }
+
+        steps.push(Step {
+            start: instruction.start(),
+            end: instruction.end(),
+            enabled,
+            contribution,
+        });
+    }
+
+    Trace {
+        steps,
+        enabled_at_end: enabled,
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = String;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part_1(text: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(interpret(Lexer::new(text), false).into())
+    }
+
+    fn part_2(text: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(interpret(Lexer::new(text), true).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,7 +229,7 @@ mod tests {
 
     #[test]
     fn day_03_test_part_1() {
-        let text = &load_input();
+        let text = &load_input().unwrap();
         assert_eq!(parse_text_with_regex(text), 183380722);
     }
 
@@ -118,7 +241,7 @@ mod tests {
 
     #[test]
     fn day_03_test_part_2() {
-        let text = &load_input();
+        let text = &load_input().unwrap();
         assert_eq!(parse_text_conditional_with_regex(text), 82733683);
     }
 
@@ -130,7 +253,227 @@ mod tests {
 
     #[test]
     fn day_03_test_part_2_new() {
-        let text = &load_input();
+        let text = &load_input().unwrap();
         assert_eq!(parse_text_conditional_with_regex_new(text), 82733683);
     }
+
+    /// Deterministic pseudo-random text built from the characters that make
+    /// up instructions.
+    fn corrupted_memory(seed: u64, len: usize) -> String {
+        let alphabet: Vec<char> = "mul(do,n't)0123456789 x".chars().collect();
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                alphabet[(state >> 33) as usize % alphabet.len()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn day_03_test_interpreter_matches_regex() {
+        let text = load_input().unwrap();
+        assert_eq!(
+            interpret(Lexer::new(&text), false),
+            parse_text_with_regex(&text)
+        );
+        assert_eq!(
+            interpret(Lexer::new(&text), true),
+            parse_text_conditional_with_regex(&text)
+        );
+
+        for seed in 0..200 {
+            let text = corrupted_memory(seed, 2000);
+            assert_eq!(
+                interpret(Lexer::new(&text), false),
+                parse_text_with_regex(&text),
+                "{}",
+                text
+            );
+            assert_eq!(
+                interpret(Lexer::new(&text), true),
+                parse_text_conditional_with_regex_new(&text),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn day_03_test_instruction_set_matches_lexer() {
+        let mut muls = InstructionSet::new();
+        muls.register(Instruction::value("mul", 2..=2, |operands| {
+            operands[0].checked_mul(operands[1])
+        }))
+        .unwrap();
+        let standard = InstructionSet::standard();
+
+        let texts = (0..200).map(|seed| corrupted_memory(seed, 2000));
+        for text in std::iter::once(load_input().unwrap()).chain(texts) {
+            assert_eq!(
+                muls.evaluate(&text).unwrap(),
+                interpret(Lexer::new(&text), false) as i64
+            );
+            assert_eq!(
+                standard.evaluate(&text).unwrap(),
+                interpret(Lexer::new(&text), true) as i64
+            );
+        }
+    }
+
+    #[test]
+    fn day_03_test_stream_matches_lexer() {
+        let path = input_path!("input.txt");
+        for chunk_size in [1, 11, 12, 13, 4096] {
+            let scanner = StreamScanner {
+                chunk_size,
+                conditional: false,
+            };
+            assert_eq!(scanner.evaluate_file(&path).unwrap(), 183380722);
+            let scanner = StreamScanner {
+                conditional: true,
+                ..scanner
+            };
+            assert_eq!(scanner.evaluate_file(&path).unwrap(), 82733683);
+        }
+
+        for seed in 0..50 {
+            let text = corrupted_memory(seed, 2000);
+            for chunk_size in [1, 7, 64] {
+                let scanner = StreamScanner {
+                    chunk_size,
+                    conditional: true,
+                };
+                assert_eq!(
+                    scanner.evaluate(text.as_bytes()).unwrap(),
+                    interpret(Lexer::new(&text), true)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn day_03_test_traces_agree() {
+        let text = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let expected = Trace {
+            steps: vec![
+                Step {
+                    start: 1,
+                    end: 9,
+                    enabled: true,
+                    contribution: Some(8),
+                },
+                Step {
+                    start: 20,
+                    end: 27,
+                    enabled: true,
+                    contribution: None,
+                },
+                Step {
+                    start: 28,
+                    end: 36,
+                    enabled: false,
+                    contribution: Some(0),
+                },
+                Step {
+                    start: 48,
+                    end: 57,
+                    enabled: false,
+                    contribution: Some(0),
+                },
+                Step {
+                    start: 59,
+                    end: 63,
+                    enabled: false,
+                    contribution: None,
+                },
+                Step {
+                    start: 64,
+                    end: 72,
+                    enabled: true,
+                    contribution: Some(40),
+                },
+            ],
+            enabled_at_end: true,
+        };
+        assert_eq!(trace_conditional_with_regex(text), expected);
+        assert_eq!(trace_conditional_with_regex_new(text), expected);
+        assert_eq!(trace(Lexer::new(text)), expected);
+        assert_eq!(InstructionSet::standard().trace(text).unwrap(), expected);
+        assert_eq!(expected.total().unwrap(), 48);
+
+        let text = load_input().unwrap();
+        let lexer = trace(Lexer::new(&text));
+        let regex = trace_conditional_with_regex_new(&text);
+        assert_eq!(regex.first_difference(&lexer), None);
+        assert_eq!(regex.total().unwrap(), 82733683);
+        let slices = trace_conditional_with_regex(&text);
+        assert_eq!(slices.first_difference(&lexer), None);
+        assert_eq!(slices.total().unwrap(), 82733683);
+
+        // The slice-based version drops everything before the first do() when
+        // there is no don't(), and misses a don't() right after a later do().
+        for (text, difference) in [
+            ("mul(2,3)", Some(0)),
+            ("mul(2,3)do()mul(1,1)", Some(0)),
+            ("don't()do()don't()mul(1,2)", Some(3)),
+            ("mul(2,3)don't()do()mul(1,1)", None),
+        ] {
+            let slices = trace_conditional_with_regex(text);
+            assert_eq!(
+                slices.total().unwrap(),
+                parse_text_conditional_with_regex(text) as i64
+            );
+            assert_eq!(
+                slices.first_difference(&trace(Lexer::new(text))),
+                difference,
+                "{}",
+                text
+            );
+        }
+
+        for seed in 0..200 {
+            let text = corrupted_memory(seed, 2000);
+            let lexer = trace(Lexer::new(&text));
+            assert_eq!(
+                trace_conditional_with_regex_new(&text).first_difference(&lexer),
+                None
+            );
+
+            // Traces differ exactly where the totals do.
+            let slices = trace_conditional_with_regex(&text);
+            let total = parse_text_conditional_with_regex(&text) as i64;
+            assert_eq!(slices.total().unwrap(), total);
+            match slices.first_difference(&lexer) {
+                None => assert_eq!(lexer.total().unwrap(), total),
+                Some(i) => {
+                    let (old, new) = (slices.steps[i], lexer.steps[i]);
+                    assert_eq!((old.start, old.end), (new.start, new.end));
+                    assert_ne!(old.enabled, new.enabled);
+                }
+            }
+        }
+
+        let mut shorter = regex.clone();
+        shorter.steps.pop();
+        assert_eq!(
+            regex.first_difference(&shorter),
+            Some(regex.steps.len() - 1)
+        );
+    }
+
+    #[test]
+    fn day_03_test_trace_render() {
+        let text = "amul(1,2)don't()mul(3,4)b";
+        let rendered = trace(Lexer::new(text)).render(text);
+        assert_eq!(
+            rendered,
+            "a\x1b[1;32mmul(1,2)\x1b[0m\x1b[1;33mdon't()\x1b[0m\x1b[31mmul(3,4)\x1b[0m\x1b[2mb\x1b[0m"
+        );
+        assert_eq!(Trace::default().render(""), "");
+    }
+
+    aoc_core::example_tests!(Day03);
 }
@@ -0,0 +1,140 @@
+use crate::lexer::{Lexer, TokenKind, MAX_INSTRUCTION_LEN};
+use aoc_core::AocError;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+/// Evaluates corrupted memory `chunk_size` bytes at a time, so dumps of any
+/// size can be read in bounded memory. Only the last few bytes of a chunk are
+/// kept back, in case an instruction continues into the next one.
+#[derive(Clone, Copy, Debug)]
+pub struct StreamScanner {
+    pub chunk_size: usize,
+    /// Whether `do()` and `don't()` switch `mul` instructions on and off.
+    pub conditional: bool,
+}
+
+impl Default for StreamScanner {
+    fn default() -> Self {
+        StreamScanner {
+            chunk_size: 64 * 1024,
+            conditional: true,
+        }
+    }
+}
+
+impl StreamScanner {
+    /// Sums the products of the enabled `mul` instructions read from `reader`.
+    pub fn evaluate(&self, mut reader: impl Read) -> Result<usize, AocError> {
+        let chunk_size = self.chunk_size.max(1);
+        let mut buffer = Vec::with_capacity(chunk_size + MAX_INSTRUCTION_LEN);
+        let mut enabled = true;
+        let mut sum = 0;
+
+        loop {
+            let kept = buffer.len();
+            buffer.resize(kept + chunk_size, 0);
+            let read = read_chunk(&mut reader, &mut buffer[kept..])?;
+            buffer.truncate(kept + read);
+            let at_end = read == 0;
+
+            // An instruction starting this close to the end may not have
+            // been read in full yet.
+            let limit = match at_end {
+                true => buffer.len(),
+                false => buffer.len().saturating_sub(MAX_INSTRUCTION_LEN - 1),
+            };
+
+            let lexer = Lexer::from_bytes(&buffer);
+            let mut position = 0;
+            while position < limit {
+                let Some((kind, end)) = lexer.instruction_at(position) else {
+                    position += 1;
+                    continue;
+                };
+
+                match kind {
+                    TokenKind::Mul(a, b) if enabled => sum += a * b,
+                    TokenKind::Do if self.conditional => enabled = true,
+                    TokenKind::Dont if self.conditional => enabled = false,
+                    _ => {}
+                }
+                position = end;
+            }
+
+            if at_end {
+                return Ok(sum);
+            }
+            buffer.drain(..position);
+        }
+    }
+
+    pub fn evaluate_file(&self, path: impl AsRef<Path>) -> Result<usize, AocError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| AocError::io(path, err))?;
+        self.evaluate(file)
+    }
+}
+
+fn read_chunk(reader: &mut impl Read, chunk: &mut [u8]) -> Result<usize, AocError> {
+    loop {
+        match reader.read(chunk) {
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            result => return result.map_err(|err| AocError::io("<reader>", err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader that hands out at most `step` bytes per read.
+    struct Trickle<'a> {
+        text: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            let length = self.step.min(buffer.len()).min(self.text.len());
+            buffer[..length].copy_from_slice(&self.text[..length]);
+            self.text = &self.text[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn day_03_test_stream_split_instructions() {
+        let text = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+        for chunk_size in 1..=text.len() + 1 {
+            for step in [1, 3, 5, 64] {
+                let reader = Trickle {
+                    text: text.as_bytes(),
+                    step,
+                };
+                let conditional = StreamScanner {
+                    chunk_size,
+                    conditional: true,
+                };
+                assert_eq!(conditional.evaluate(reader).unwrap(), 48, "{}", chunk_size);
+
+                let plain = StreamScanner {
+                    conditional: false,
+                    ..conditional
+                };
+                assert_eq!(plain.evaluate(text.as_bytes()).unwrap(), 161);
+            }
+        }
+    }
+
+    #[test]
+    fn day_03_test_stream_errors() {
+        assert_eq!(StreamScanner::default().evaluate(&b""[..]).unwrap(), 0);
+        assert!(matches!(
+            StreamScanner::default().evaluate_file("no_such_dump.txt"),
+            Err(AocError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,82 @@
+use aoc_core::AocError;
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+const GREEN: &str = "\x1b[1;32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[1;33m";
+
+/// One instruction reached while evaluating, at byte range `start..end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub start: usize,
+    pub end: usize,
+    /// Whether instructions were enabled when this one was reached.
+    pub enabled: bool,
+    /// What the instruction added to the total, or `None` for instructions
+    /// that only switch others on and off.
+    pub contribution: Option<i64>,
+}
+
+/// Every instruction an evaluation went through, in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    /// Whether instructions were enabled after the last one.
+    pub enabled_at_end: bool,
+}
+
+impl Trace {
+    /// The sum of the contributions, failing if it overflows.
+    pub fn total(&self) -> Result<i64, AocError> {
+        self.steps
+            .iter()
+            .filter_map(|step| step.contribution)
+            .try_fold(0i64, |total, contribution| total.checked_add(contribution))
+            .ok_or_else(|| AocError::invalid("Total overflows an i64"))
+    }
+
+    /// Index of the first step where two traces of the same text disagree.
+    pub fn first_difference(&self, other: &Trace) -> Option<usize> {
+        let length = self.steps.len().max(other.steps.len());
+        (0..length).find(|&i| self.steps.get(i) != other.steps.get(i))
+    }
+
+    /// `text` with terminal colours: disabled stretches are dimmed, counted
+    /// instructions green, skipped ones red and switches yellow.
+    pub fn render(&self, text: &str) -> String {
+        let mut rendered = String::new();
+        let mut position = 0;
+
+        for step in &self.steps {
+            // Garbage is shown in the state the next instruction is reached in.
+            let garbage = &text[position..step.start];
+            push_span(&mut rendered, garbage, (!step.enabled).then_some(DIM));
+
+            let style = match step.contribution {
+                None => YELLOW,
+                Some(_) if step.enabled => GREEN,
+                Some(_) => RED,
+            };
+            push_span(&mut rendered, &text[step.start..step.end], Some(style));
+            position = step.end;
+        }
+
+        let rest = &text[position..];
+        push_span(&mut rendered, rest, (!self.enabled_at_end).then_some(DIM));
+
+        rendered
+    }
+}
+
+fn push_span(rendered: &mut String, text: &str, style: Option<&str>) {
+    match style {
+        _ if text.is_empty() => {}
+        Some(style) => {
+            rendered.push_str(style);
+            rendered.push_str(text);
+            rendered.push_str(RESET);
+        }
+        None => rendered.push_str(text),
+    }
+}
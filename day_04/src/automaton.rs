@@ -0,0 +1,83 @@
+use std::collections::{HashMap, VecDeque};
+
+/// An Aho-Corasick automaton: a trie of the patterns where every node also
+/// knows the longest proper suffix of its path that is in the trie, so a text
+/// can be searched for every pattern at once, one character at a time.
+#[derive(Clone, Debug)]
+pub(crate) struct Automaton<T> {
+    children: Vec<HashMap<char, usize>>,
+    fail: Vec<usize>,
+    /// Each pattern ending at a node, with its length in characters.
+    outputs: Vec<Vec<(T, usize)>>,
+}
+
+impl<T: Clone> Automaton<T> {
+    /// Builds the automaton for `patterns`, each tagged with the value to
+    /// report when it is found. Empty patterns are never found.
+    pub(crate) fn new(patterns: impl IntoIterator<Item = (Vec<char>, T)>) -> Self {
+        let mut automaton = Automaton {
+            children: vec![HashMap::new()],
+            fail: vec![0],
+            outputs: vec![Vec::new()],
+        };
+
+        for (pattern, tag) in patterns {
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut node = 0;
+            for &letter in &pattern {
+                node = match automaton.children[node].get(&letter) {
+                    Some(&child) => child,
+                    None => {
+                        let child = automaton.children.len();
+                        automaton.children.push(HashMap::new());
+                        automaton.fail.push(0);
+                        automaton.outputs.push(Vec::new());
+                        automaton.children[node].insert(letter, child);
+                        child
+                    }
+                };
+            }
+            automaton.outputs[node].push((tag, pattern.len()));
+        }
+
+        // Breadth first, so a node's suffix link is final before its children's.
+        let mut queue: VecDeque<usize> = automaton.children[0].values().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(char, usize)> = automaton.children[node]
+                .iter()
+                .map(|(&letter, &child)| (letter, child))
+                .collect();
+
+            for (letter, child) in children {
+                let fail = automaton.next(automaton.fail[node], letter);
+                automaton.fail[child] = fail;
+                let inherited = automaton.outputs[fail].clone();
+                automaton.outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        automaton
+    }
+
+    /// The node reached from `node` on reading `letter`.
+    pub(crate) fn next(&self, mut node: usize, letter: char) -> usize {
+        loop {
+            if let Some(&child) = self.children[node].get(&letter) {
+                return child;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.fail[node];
+        }
+    }
+
+    /// The patterns ending at `node`.
+    pub(crate) fn outputs(&self, node: usize) -> &[(T, usize)] {
+        &self.outputs[node]
+    }
+}
@@ -1,80 +1,185 @@
+mod automaton;
+mod pattern;
+
+use aoc_core::{input_path, read_input, Answer, AocError, Solution};
+use aoc_grid::{offset, Grid, DIRECTIONS_8};
+use automaton::Automaton;
 use ndarray::{Array2, Axis};
-use std::fs;
-
-const DIRECTIONS: [(i32, i32); 8] = [
-    (1, 0),
-    (-1, 0),
-    (0, 1),
-    (0, -1),
-    (1, 1),
-    (1, -1),
-    (-1, 1),
-    (-1, -1),
-];
-
-pub fn load_input() -> String {
+
+pub use pattern::{Pattern, PatternMatch, X_MAS};
+
+/// One of each pair of opposite directions in `DIRECTIONS_8`: along rows,
+/// down columns and the two diagonals.
+const LINE_DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+pub fn load_input() -> Result<String, AocError> {
     let file_path = "input.txt";
-    fs::read_to_string(file_path).expect("Could not read from file")
+    read_input(input_path!(file_path))
+}
+
+pub fn string_to_ndarray(input: &str) -> Result<Array2<char>, AocError> {
+    Ok(Grid::parse(input)?.into_array())
 }
 
-pub fn string_to_ndarray(input: &str) -> Array2<char> {
-    let rows: Vec<&str> = input.lines().collect();
-    let nrows = rows.len();
-    let ncols = rows[0].len();
-    let mut array = Array2::from_elem((nrows, ncols), ' ');
+pub fn search_array(word: &str, array: &Array2<char>) -> usize {
+    find_word(word, array).len()
+}
+
+/// Every occurrence of `word` in any of the eight directions, in row-major
+/// order of their start cells.
+pub fn find_word(word: &str, array: &Array2<char>) -> Vec<WordMatch> {
+    let word_chars: Vec<char> = word.chars().collect();
+    let mut matches = Vec::new();
+
+    let Some((&first, rest)) = word_chars.split_first() else {
+        return matches;
+    };
 
-    for (i, row) in rows.iter().enumerate() {
-        for (j, letter) in row.chars().enumerate() {
-            array[[i, j]] = letter;
+    for (start, &letter) in array.indexed_iter() {
+        if letter != first {
+            continue;
+        }
+
+        for &direction in &DIRECTIONS_8 {
+            let mut position = start;
+            let found = rest
+                .iter()
+                .all(|letter| match offset(array.dim(), position, direction) {
+                    Some(next) if array[next] == *letter => {
+                        position = next;
+                        true
+                    }
+                    _ => false,
+                });
+            if found {
+                matches.push(WordMatch {
+                    start,
+                    direction,
+                    length: word_chars.len(),
+                });
+            }
         }
     }
 
-    array
+    matches
 }
 
-pub fn search_array(word: &str, array: &Array2<char>) -> usize {
-    let n = array.len_of(Axis(0));
-    let m = array.len_of(Axis(1));
-    let word_chars: Vec<char> = word.chars().collect();
-    let mut count = 0;
-
-    for i in 0..n {
-        for j in 0..m {
-            if array[[i, j]] == word.chars().next().unwrap() {
-                for &(dir_x, dir_y) in &DIRECTIONS {
-                    let mut found = true;
-                    for (k, letter) in word_chars.iter().enumerate() {
-                        let x = i as i32 + k as i32 * dir_x;
-                        let y = j as i32 + k as i32 * dir_y;
-
-                        if x < 0
-                            || x >= n as i32
-                            || y < 0
-                            || y >= m as i32
-                            || array[[x as usize, y as usize]] != *letter
-                        {
-                            found = false;
-                            break;
-                        }
-                    }
-                    if found {
-                        count += 1;
-                    }
+/// A word of `length` letters found in the grid, read from `start` in
+/// `direction`, one of `DIRECTIONS_8`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WordMatch {
+    pub start: (usize, usize),
+    pub direction: (isize, isize),
+    pub length: usize,
+}
+
+impl WordMatch {
+    /// The cells the word covers, from its first letter to its last.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.length).map(|k| {
+            let k = k as isize;
+            (
+                self.start.0.wrapping_add_signed(k * self.direction.0),
+                self.start.1.wrapping_add_signed(k * self.direction.1),
+            )
+        })
+    }
+}
+
+/// Every place one word of a dictionary was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WordMatches {
+    pub word: String,
+    /// Ordered by start cell, then by direction as in `DIRECTIONS_8`.
+    pub matches: Vec<WordMatch>,
+}
+
+impl WordMatches {
+    pub fn count(&self) -> usize {
+        self.matches.len()
+    }
+}
+
+/// Finds every word of `words` in all eight directions, like
+/// [`search_array`], in a single pass along each row, column and diagonal.
+/// Results are in the order of `words`; empty words are never found.
+pub fn search_words(words: &[&str], array: &Array2<char>) -> Vec<WordMatches> {
+    // A word read backwards along a line is the word in the opposite
+    // direction, so each line is scanned once for the words and their reverses.
+    let patterns = words.iter().enumerate().flat_map(|(i, word)| {
+        let forward: Vec<char> = word.chars().collect();
+        let backward: Vec<char> = word.chars().rev().collect();
+        [(forward, (i, false)), (backward, (i, true))]
+    });
+    let automaton = Automaton::new(patterns);
+
+    let mut results: Vec<WordMatches> = words
+        .iter()
+        .map(|word| WordMatches {
+            word: word.to_string(),
+            matches: Vec::new(),
+        })
+        .collect();
+
+    for direction in LINE_DIRECTIONS {
+        let opposite = (-direction.0, -direction.1);
+        let line_starts = array
+            .indexed_iter()
+            .map(|(position, _)| position)
+            .filter(|&position| offset(array.dim(), position, opposite).is_none());
+
+        for line_start in line_starts {
+            let line: Vec<(usize, usize)> = std::iter::successors(Some(line_start), |&position| {
+                offset(array.dim(), position, direction)
+            })
+            .collect();
+
+            let mut node = 0;
+            for (end, &position) in line.iter().enumerate() {
+                node = automaton.next(node, array[position]);
+
+                for &((word, backward), length) in automaton.outputs(node) {
+                    let found = match backward {
+                        false => WordMatch {
+                            start: line[end + 1 - length],
+                            direction,
+                            length,
+                        },
+                        true => WordMatch {
+                            start: position,
+                            direction: opposite,
+                            length,
+                        },
+                    };
+                    results[word].matches.push(found);
                 }
             }
         }
     }
 
-    count
+    for result in &mut results {
+        result.matches.sort_by_key(|found| {
+            let direction = DIRECTIONS_8.iter().position(|&d| d == found.direction);
+            (found.start, direction)
+        });
+    }
+
+    results
 }
 
 pub fn search_array_part_2(array: &Array2<char>) -> usize {
+    find_crosses(array).len()
+}
+
+/// Every X-MAS: two `MAS` words crossing diagonally at their `A`, in
+/// row-major order of the `A`. The down-right word comes first.
+pub fn find_crosses(array: &Array2<char>) -> Vec<[WordMatch; 2]> {
     let n = array.len_of(Axis(0));
     let m = array.len_of(Axis(1));
-    let mut count = 0;
+    let mut crosses = Vec::new();
 
-    for i in 1..(n - 1) {
-        for j in 1..(m - 1) {
+    for i in 1..n.saturating_sub(1) {
+        for j in 1..m.saturating_sub(1) {
             let letter_1 = array[[i - 1, j - 1]];
             let letter_2 = array[[i, j]];
             let letter_3 = array[[i + 1, j + 1]];
@@ -82,25 +187,81 @@ pub fn search_array_part_2(array: &Array2<char>) -> usize {
             let letter_4 = array[[i - 1, j + 1]];
             let letter_5 = array[[i + 1, j - 1]];
 
-            let pattern = [('M', 'A', 'S'), ('S', 'A', 'M')];
-            for down in pattern {
-                for up in pattern {
-                    if (letter_1, letter_2, letter_3) == down
-                        && (letter_4, letter_2, letter_5) == up
+            // Each reading of a diagonal, with where that reading starts.
+            let down = [
+                (('M', 'A', 'S'), (i - 1, j - 1), (1, 1)),
+                (('S', 'A', 'M'), (i + 1, j + 1), (-1, -1)),
+            ];
+            let up = [
+                (('M', 'A', 'S'), (i - 1, j + 1), (1, -1)),
+                (('S', 'A', 'M'), (i + 1, j - 1), (-1, 1)),
+            ];
+            for (down_letters, down_start, down_direction) in down {
+                for (up_letters, up_start, up_direction) in up {
+                    if (letter_1, letter_2, letter_3) == down_letters
+                        && (letter_4, letter_2, letter_5) == up_letters
                     {
-                        count += 1;
+                        crosses.push([
+                            WordMatch {
+                                start: down_start,
+                                direction: down_direction,
+                                length: 3,
+                            },
+                            WordMatch {
+                                start: up_start,
+                                direction: up_direction,
+                                length: 3,
+                            },
+                        ]);
                     }
                 }
             }
         }
     }
 
-    count
+    crosses
+}
+
+/// The grid with every letter outside `matches` replaced by `.`, the way the
+/// puzzle shows its examples.
+pub fn render_matches<'a>(
+    array: &Array2<char>,
+    matches: impl IntoIterator<Item = &'a WordMatch>,
+) -> String {
+    let mut shown = Array2::from_elem(array.dim(), '.');
+    for found in matches {
+        for (i, j) in found.cells() {
+            shown[[i, j]] = array[[i, j]];
+        }
+    }
+
+    Grid::from_array(shown).render_with(|&letter| letter)
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Array2<char>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        string_to_ndarray(input)
+    }
+
+    fn part_1(grid: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(search_array("XMAS", grid).into())
+    }
+
+    fn part_2(grid: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(search_array_part_2(grid).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn day_04_test_part1_simple() {
@@ -115,14 +276,14 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
 
-        let grid = string_to_ndarray(&input);
+        let grid = string_to_ndarray(input).unwrap();
         assert_eq!(search_array("XMAS", &grid), 18);
     }
 
     #[test]
     fn day_04_test_part1() {
-        let input = load_input();
-        let grid = string_to_ndarray(&input);
+        let input = load_input().unwrap();
+        let grid = string_to_ndarray(&input).unwrap();
         assert_eq!(search_array("XMAS", &grid), 2358);
     }
 
@@ -139,14 +300,187 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
 
-        let grid = string_to_ndarray(&input);
+        let grid = string_to_ndarray(input).unwrap();
         assert_eq!(search_array_part_2(&grid), 9);
     }
 
     #[test]
     fn day_04_test_part2() {
-        let input = load_input();
-        let grid = string_to_ndarray(&input);
+        let input = load_input().unwrap();
+        let grid = string_to_ndarray(&input).unwrap();
         assert_eq!(search_array_part_2(&grid), 1737);
     }
+
+    #[test]
+    fn day_04_test_solution() {
+        let input = Day04::parse(&load_input().unwrap()).unwrap();
+        assert_eq!(Day04::part_1(&input).unwrap(), Answer::Unsigned(2358));
+        assert_eq!(Day04::part_2(&input).unwrap(), Answer::Unsigned(1737));
+    }
+
+    #[test]
+    fn day_04_test_search_words() {
+        let words = ["XMAS", "SAMX", "MAS", "X", "XMASX", "AMA"];
+        for text in [
+            load_input().unwrap(),
+            read_input(input_path!("input_simple.txt")).unwrap(),
+        ] {
+            let grid = string_to_ndarray(&text).unwrap();
+            let results = search_words(&words, &grid);
+
+            for (word, result) in words.iter().zip(&results) {
+                assert_eq!(result.word, *word);
+                assert_eq!(result.count(), search_array(word, &grid), "{}", word);
+                assert_eq!(result.matches, find_word(word, &grid), "{}", word);
+            }
+        }
+
+        let grid = string_to_ndarray("XMAS\nMMAA\nAXAS\nSSSS").unwrap();
+        let results = search_words(&["XMAS", "", "AA"], &grid);
+        assert_eq!(
+            results[0].matches,
+            [(1, 0), (0, 1), (1, 1)].map(|direction| WordMatch {
+                start: (0, 0),
+                direction,
+                length: 4,
+            })
+        );
+        assert_eq!(results[1].count(), 0);
+        assert_eq!(
+            results[2].matches,
+            [
+                ((0, 2), (1, 0)),
+                ((0, 2), (1, 1)),
+                ((1, 2), (1, 0)),
+                ((1, 2), (-1, 0)),
+                ((1, 2), (0, 1)),
+                ((1, 3), (0, -1)),
+                ((1, 3), (1, -1)),
+                ((1, 3), (-1, -1)),
+                ((2, 2), (-1, 0)),
+                ((2, 2), (-1, 1)),
+            ]
+            .map(|(start, direction)| WordMatch {
+                start,
+                direction,
+                length: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn day_04_test_match_locations() {
+        let grid =
+            string_to_ndarray(&read_input(input_path!("input_simple.txt")).unwrap()).unwrap();
+
+        let matches = find_word("XMAS", &grid);
+        assert_eq!(matches.len(), 18);
+        assert_eq!(
+            matches[0],
+            WordMatch {
+                start: (0, 4),
+                direction: (1, 1),
+                length: 4
+            }
+        );
+        assert_eq!(
+            matches[0].cells().collect::<Vec<_>>(),
+            vec![(0, 4), (1, 5), (2, 6), (3, 7)]
+        );
+        assert!(matches
+            .iter()
+            .all(|found| found.cells().map(|(i, j)| grid[[i, j]]).eq("XMAS".chars())));
+        assert_eq!(
+            render_matches(&grid, &matches),
+            "....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX
+"
+        );
+
+        let crosses = find_crosses(&grid);
+        assert_eq!(crosses.len(), 9);
+        assert_eq!(
+            render_matches(&grid, crosses.iter().flatten()),
+            ".M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+..........
+"
+        );
+        assert!(find_word("", &grid).is_empty());
+    }
+
+    #[test]
+    fn day_04_test_patterns() {
+        for text in [
+            load_input().unwrap(),
+            read_input(input_path!("input_simple.txt")).unwrap(),
+        ] {
+            let grid = string_to_ndarray(&text).unwrap();
+
+            let x_mas = Pattern::parse(X_MAS).unwrap();
+            let crosses: Vec<Vec<(usize, usize)>> = find_crosses(&grid)
+                .iter()
+                .map(|cross| {
+                    let mut cells: Vec<_> = cross.iter().flat_map(WordMatch::cells).collect();
+                    cells.sort();
+                    cells.dedup();
+                    cells
+                })
+                .collect();
+            let found: Vec<Vec<(usize, usize)>> = x_mas
+                .find(&grid)
+                .into_iter()
+                .map(|found| found.cells)
+                .collect();
+            assert_eq!(found, crosses);
+
+            let diagonal = Pattern::parse("X...\n.M..\n..A.\n...S").unwrap();
+            let diagonal_words = find_word("XMAS", &grid)
+                .iter()
+                .filter(|found| found.direction.0 != 0 && found.direction.1 != 0)
+                .count();
+            assert_eq!(diagonal.count(&grid), diagonal_words);
+
+            let line = Pattern::parse("XMAS").unwrap();
+            assert_eq!(
+                line.count(&grid) + diagonal.count(&grid),
+                search_array("XMAS", &grid)
+            );
+
+            // A plus is an across and a down MAS sharing their A.
+            let plus = Pattern::parse(".M.\nMAS\n.S.").unwrap();
+            let mas = find_word("MAS", &grid);
+            let centres = |horizontal: bool| -> HashSet<(usize, usize)> {
+                mas.iter()
+                    .filter(|found| (found.direction.0 == 0) == horizontal)
+                    .filter(|found| found.direction.0 == 0 || found.direction.1 == 0)
+                    .map(|found| found.cells().nth(1).unwrap())
+                    .collect()
+            };
+            assert_eq!(
+                plus.count(&grid),
+                centres(true).intersection(&centres(false)).count()
+            );
+        }
+
+        let grid = string_to_ndarray("MAS").unwrap();
+        assert_eq!(Pattern::parse(X_MAS).unwrap().count(&grid), 0);
+    }
+
+    aoc_core::example_tests!(Day04);
 }
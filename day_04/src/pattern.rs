@@ -0,0 +1,181 @@
+use aoc_core::AocError;
+use aoc_grid::Grid;
+use ndarray::{s, Array2, Axis};
+use std::collections::HashSet;
+
+/// The X-MAS of part 2 as a template.
+pub const X_MAS: &str = "M.S\n.A.\nM.S";
+
+/// Where a [`Pattern`] was found: the top-left corner of the template in
+/// orientation `orientation` (an index into [`Pattern::orientations`]), and
+/// the cells its letters cover.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternMatch {
+    pub origin: (usize, usize),
+    pub orientation: usize,
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// A small template of letters and wildcards, found in the grid in any of
+/// its rotations and reflections.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    orientations: Vec<Array2<Option<char>>>,
+}
+
+impl Pattern {
+    /// Parses a template in which `.` matches any letter.
+    pub fn parse(template: &str) -> Result<Self, AocError> {
+        Self::parse_with_wildcard(template, '.')
+    }
+
+    pub fn parse_with_wildcard(template: &str, wildcard: char) -> Result<Self, AocError> {
+        let cells = Grid::parse_with(template, |letter| {
+            Ok::<_, AocError>((letter != wildcard).then_some(letter))
+        })?
+        .into_array();
+
+        if cells.iter().all(Option::is_none) {
+            return Err(AocError::invalid("Pattern needs at least one letter"));
+        }
+
+        Ok(Pattern::new(cells))
+    }
+
+    /// A pattern from its cells, `None` being a wildcard.
+    pub fn new(cells: Array2<Option<char>>) -> Self {
+        let mut orientations = Vec::new();
+        let mut rotated = cells;
+
+        for _ in 0..4 {
+            let reflected = rotated.slice(s![.., ..;-1]).to_owned();
+            let next = rotated.t().slice(s![.., ..;-1]).to_owned();
+
+            // Symmetric templates look the same in several orientations,
+            // which would find the same cells twice. Templates that only
+            // differ in their wildcard padding are left to `find`.
+            for orientation in [rotated, reflected] {
+                if !orientations.contains(&orientation) {
+                    orientations.push(orientation);
+                }
+            }
+            rotated = next;
+        }
+
+        Pattern { orientations }
+    }
+
+    /// The distinct rotations and reflections of the template, starting with
+    /// the template itself.
+    pub fn orientations(&self) -> &[Array2<Option<char>>] {
+        &self.orientations
+    }
+
+    pub fn count(&self, array: &Array2<char>) -> usize {
+        self.find(array).len()
+    }
+
+    /// Every occurrence of the pattern, ordered by origin, then orientation.
+    /// Orientations covering the same cells with their letters, which
+    /// wildcard padding allows, are one occurrence, kept in the first of them.
+    pub fn find(&self, array: &Array2<char>) -> Vec<PatternMatch> {
+        let n = array.len_of(Axis(0));
+        let m = array.len_of(Axis(1));
+        let mut matches = Vec::new();
+
+        for (index, orientation) in self.orientations.iter().enumerate() {
+            let (height, width) = orientation.dim();
+            if height > n || width > m {
+                continue;
+            }
+
+            for i in 0..=(n - height) {
+                for j in 0..=(m - width) {
+                    let found = orientation.indexed_iter().all(|((di, dj), letter)| {
+                        letter.is_none_or(|letter| array[[i + di, j + dj]] == letter)
+                    });
+                    if !found {
+                        continue;
+                    }
+
+                    let cells = orientation
+                        .indexed_iter()
+                        .filter(|(_, letter)| letter.is_some())
+                        .map(|((di, dj), _)| (i + di, j + dj))
+                        .collect();
+                    matches.push(PatternMatch {
+                        origin: (i, j),
+                        orientation: index,
+                        cells,
+                    });
+                }
+            }
+        }
+
+        matches.sort_by_key(|found| (found.origin, found.orientation));
+
+        // Cells come in row-major order, so equal sets are equal vectors.
+        let mut seen = HashSet::new();
+        matches.retain(|found| seen.insert(found.cells.clone()));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_04_test_pattern_orientations() {
+        let x_mas = Pattern::parse(X_MAS).unwrap();
+        assert_eq!(x_mas.orientations().len(), 4);
+
+        let corner = Pattern::parse("AB\n.C").unwrap();
+        assert_eq!(corner.orientations().len(), 8);
+        assert_eq!(
+            corner.orientations()[1],
+            ndarray::array![[Some('B'), Some('A')], [Some('C'), None]]
+        );
+
+        let square = Pattern::parse("AA\nAA").unwrap();
+        assert_eq!(square.orientations().len(), 1);
+
+        let line = Pattern::parse("XMAS").unwrap();
+        assert_eq!(line.orientations().len(), 4);
+        assert_eq!(line.orientations()[2].dim(), (4, 1));
+    }
+
+    #[test]
+    fn day_04_test_pattern_padding() {
+        let grid = ndarray::array![['A', 'B'], ['C', 'A']];
+
+        let padded = Pattern::parse("A.").unwrap();
+        assert_eq!(padded.orientations().len(), 4);
+        let matches = padded.find(&grid);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|found| found.cells.clone())
+                .collect::<Vec<_>>(),
+            vec![vec![(0, 0)], vec![(1, 1)]]
+        );
+
+        // An A next to a B, across or down, but padding must still fit.
+        assert_eq!(Pattern::parse("AB").unwrap().count(&grid), 2);
+        assert_eq!(Pattern::parse("AB..").unwrap().count(&grid), 0);
+        assert_eq!(Pattern::parse("..A..").unwrap().count(&grid), 0);
+    }
+
+    #[test]
+    fn day_04_test_pattern_invalid() {
+        assert!(Pattern::parse("...\n...").is_err());
+        assert!(Pattern::parse("").is_err());
+        assert!(Pattern::parse("M.S\n.A").is_err());
+        assert_eq!(
+            Pattern::parse_with_wildcard("M?S", '?')
+                .unwrap()
+                .orientations()[0],
+            ndarray::array![[Some('M'), None, Some('S')]]
+        );
+    }
+}
@@ -1,35 +1,37 @@
+use aoc_core::{input_path, parse_field, read_input, Answer, AocError, Solution};
 use std::collections::HashMap;
-use std::fs;
 
-pub fn load_input(input_file: &str) -> (HashMap<usize, Vec<usize>>, Vec<Vec<usize>>) {
-    let text = fs::read_to_string(input_file).expect("Could not read from file");
+pub type Rules = HashMap<usize, Vec<usize>>;
+
+pub fn load_input(input_file: &str) -> Result<(Rules, Vec<Vec<usize>>), AocError> {
+    let text = read_input(input_path!(input_file))?;
+    parse_input(&text)
+}
+
+pub fn parse_input(text: &str) -> Result<(Rules, Vec<Vec<usize>>), AocError> {
     let mut rules = HashMap::new();
     let mut updates = Vec::new();
 
-    for line in text.lines() {
-        if line.contains("|") {
-            let mut line_iter = line.split_terminator("|");
+    for (i, line) in text.lines().enumerate() {
+        if let Some((before, after)) = line.split_once('|') {
             rules
-                .entry(line_iter.next().unwrap().parse().unwrap())
+                .entry(parse_field(i, line, before)?)
                 .or_insert_with(Vec::new)
-                .push(line_iter.next().unwrap().parse().unwrap());
+                .push(parse_field(i, line, after)?);
         } else if line.contains(",") {
             let line_vec: Vec<usize> = line
                 .split_terminator(",")
-                .map(|num| num.parse().unwrap())
-                .collect();
+                .map(|num| parse_field(i, line, num))
+                .collect::<Result<_, _>>()?;
 
             updates.push(line_vec);
         }
     }
 
-    (rules, updates)
+    Ok((rules, updates))
 }
 
-pub fn sum_middle_of_updates_in_right_order(
-    rules: HashMap<usize, Vec<usize>>,
-    updates: Vec<Vec<usize>>,
-) -> usize {
+pub fn sum_middle_of_updates_in_right_order(rules: &Rules, updates: &[Vec<usize>]) -> usize {
     let mut correct_middle_entries = Vec::new();
 
     for update in updates {
@@ -52,13 +54,11 @@ pub fn sum_middle_of_updates_in_right_order(
     correct_middle_entries.iter().sum()
 }
 
-pub fn sum_middle_of_updates_in_wrong_order(
-    rules: HashMap<usize, Vec<usize>>,
-    updates: Vec<Vec<usize>>,
-) -> usize {
+pub fn sum_middle_of_updates_in_wrong_order(rules: &Rules, updates: &[Vec<usize>]) -> usize {
     let mut wrong_middle_entries = Vec::new();
 
-    for mut update in updates {
+    for update in updates {
+        let mut update = update.clone();
         let mut correct_count = 0;
         for i in 0..(update.len() - 1) {
             if let Some(rule) = rules.get(&update[i]) {
@@ -102,31 +102,66 @@ pub fn sum_middle_of_updates_in_wrong_order(
     wrong_middle_entries.iter().sum()
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = (Rules, Vec<Vec<usize>>);
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1((rules, updates): &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(sum_middle_of_updates_in_right_order(rules, updates).into())
+    }
+
+    fn part_2((rules, updates): &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(sum_middle_of_updates_in_wrong_order(rules, updates).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn day_05_part_1_simple() {
-        let (rules, updates) = load_input("input_simple.txt");
-        assert_eq!(sum_middle_of_updates_in_right_order(rules, updates), 143);
+        let (rules, updates) = load_input("input_simple.txt").unwrap();
+        assert_eq!(sum_middle_of_updates_in_right_order(&rules, &updates), 143);
     }
 
     #[test]
     fn day_05_part_1() {
-        let (rules, updates) = load_input("input.txt");
-        assert_eq!(sum_middle_of_updates_in_right_order(rules, updates), 5108);
+        let (rules, updates) = load_input("input.txt").unwrap();
+        assert_eq!(sum_middle_of_updates_in_right_order(&rules, &updates), 5108);
     }
 
     #[test]
     fn day_05_part_2_simple() {
-        let (rules, updates) = load_input("input_simple.txt");
-        assert_eq!(sum_middle_of_updates_in_wrong_order(rules, updates), 123);
+        let (rules, updates) = load_input("input_simple.txt").unwrap();
+        assert_eq!(sum_middle_of_updates_in_wrong_order(&rules, &updates), 123);
     }
 
     #[test]
     fn day_05_part_2() {
-        let (rules, updates) = load_input("input.txt");
-        assert_eq!(sum_middle_of_updates_in_wrong_order(rules, updates), 7380);
+        let (rules, updates) = load_input("input.txt").unwrap();
+        assert_eq!(sum_middle_of_updates_in_wrong_order(&rules, &updates), 7380);
+    }
+
+    aoc_core::example_tests!(Day05);
+
+    #[test]
+    fn day_05_malformed_rule() {
+        let err = parse_input("47|53\n97|1x\n\n75,47,61").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 4,
+                ..
+            }
+        ));
     }
 }
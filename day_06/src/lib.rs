@@ -1,26 +1,15 @@
+use aoc_core::{input_path, read_input, Answer, AocError, Solution};
+use aoc_grid::Grid;
 use ndarray::Array2;
+use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
-use std::error::Error;
-use std::fs;
 
-pub fn load_input(file_path: &str) -> String {
-    fs::read_to_string(file_path).expect("Could not read from file")
+pub fn load_input(file_path: &str) -> Result<String, AocError> {
+    read_input(input_path!(file_path))
 }
 
-pub fn create_map(input: &str) -> Array2<char> {
-    let rows: Vec<&str> = input.lines().collect();
-    let nrows = rows.len();
-    let ncols = rows[0].len();
-
-    let mut array = Array2::from_elem((nrows, ncols), ' ');
-
-    for (i, row) in rows.iter().enumerate() {
-        for (j, letter) in row.chars().enumerate() {
-            array[[i, j]] = letter;
-        }
-    }
-
-    array
+pub fn create_map(input: &str) -> Result<Array2<char>, AocError> {
+    Ok(Grid::parse(input)?.into_array())
 }
 
 pub fn find_initial_location(room_map: &Array2<char>, target: char) -> Option<Location> {
@@ -181,22 +170,77 @@ impl Guard {
             > 1
     }
 
-    pub fn track_guard(&mut self, room_map: &Array2<char>) -> Result<(), Box<dyn Error>> {
+    pub fn track_guard(&mut self, room_map: &Array2<char>) -> Result<(), AocError> {
         while self.on_map {
             self.move_in_direction(room_map);
             if self.check_if_guard_in_loop() {
-                return Err("Guard in loop".into());
+                return Err(AocError::invalid("Guard in loop"));
             }
         }
         Ok(())
     }
 }
 
+/// Counts the positions where a single new obstruction traps the guard in a
+/// loop. Only cells on the guard's original route can change its path.
+pub fn count_loop_obstructions(room_map: &Array2<char>) -> Result<usize, AocError> {
+    let initial_location =
+        find_initial_location(room_map, '^').ok_or(AocError::invalid("Could not find guard"))?;
+
+    let mut guard = Guard::new(initial_location, Direction::Up);
+    let _ = guard.track_guard(room_map);
+    let mut unique_locations = HashSet::new();
+    for &entry in guard.location_log.values() {
+        unique_locations.insert(entry.0);
+    }
+
+    let loop_count = unique_locations
+        .par_iter()
+        .filter(|location| {
+            let mut cloned_map = room_map.clone();
+            let cell = &mut cloned_map[[location.row as usize, location.column as usize]];
+            if *cell != '.' {
+                return false;
+            }
+            *cell = '#';
+
+            let mut guard = Guard::new(initial_location, Direction::Up);
+            guard.track_guard(&cloned_map).is_err()
+        })
+        .count();
+
+    Ok(loop_count)
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Input = Array2<char>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        create_map(input)
+    }
+
+    fn part_1(room_map: &Self::Input) -> aoc_core::Result<Answer> {
+        let initial_location = find_initial_location(room_map, '^')
+            .ok_or(AocError::invalid("Could not find guard"))?;
+        let mut guard = Guard::new(initial_location, Direction::Up);
+        guard.track_guard(room_map)?;
+
+        Ok(guard.unique_locations_count().into())
+    }
+
+    fn part_2(room_map: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(count_loop_obstructions(room_map)?.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use indicatif::ProgressBar;
-    use rayon::prelude::*;
     use std::sync::{
         atomic::{AtomicI32, Ordering},
         Arc,
@@ -204,8 +248,8 @@ mod tests {
 
     #[test]
     fn day_06_part_1_simple() {
-        let input = load_input("input_simple.txt");
-        let room_map = create_map(&input);
+        let input = load_input("input_simple.txt").unwrap();
+        let room_map = create_map(&input).unwrap();
         let initial_location = find_initial_location(&room_map, '^');
         let mut guard = Guard::new(initial_location.unwrap(), Direction::Up);
         let _ = guard.track_guard(&room_map);
@@ -215,8 +259,8 @@ mod tests {
 
     #[test]
     fn day_06_part_1() {
-        let input = load_input("input.txt");
-        let room_map = create_map(&input);
+        let input = load_input("input.txt").unwrap();
+        let room_map = create_map(&input).unwrap();
         let initial_location = find_initial_location(&room_map, '^');
         let mut guard = Guard::new(initial_location.unwrap(), Direction::Up);
         let _ = guard.track_guard(&room_map);
@@ -226,8 +270,8 @@ mod tests {
 
     #[test]
     fn day_06_part_2_simple_should_fail() {
-        let input = load_input("input_simple.txt");
-        let mut room_map = create_map(&input);
+        let input = load_input("input_simple.txt").unwrap();
+        let mut room_map = create_map(&input).unwrap();
         if let Some(cell) = room_map.get_mut((6, 3)) {
             *cell = '#';
         };
@@ -237,10 +281,17 @@ mod tests {
         assert!(guard.track_guard(&room_map).is_err());
     }
 
+    #[test]
+    fn day_06_part_2_simple_count_loop_obstructions() {
+        let input = load_input("input_simple.txt").unwrap();
+        let room_map = create_map(&input).unwrap();
+        assert_eq!(count_loop_obstructions(&room_map).unwrap(), 6);
+    }
+
     #[test]
     fn day_06_part_2_simple() {
-        let input = load_input("input_simple.txt");
-        let room_map = create_map(&input);
+        let input = load_input("input_simple.txt").unwrap();
+        let room_map = create_map(&input).unwrap();
         let initial_location = find_initial_location(&room_map, '^');
 
         let mut guard = Guard::new(initial_location.unwrap(), Direction::Up);
@@ -273,8 +324,8 @@ mod tests {
 
     #[test]
     fn day_06_part_2() {
-        let input = load_input("input.txt");
-        let room_map = create_map(&input);
+        let input = load_input("input.txt").unwrap();
+        let room_map = create_map(&input).unwrap();
         let initial_location = find_initial_location(&room_map, '^');
 
         let mut guard = Guard::new(initial_location.unwrap(), Direction::Up);
@@ -304,4 +355,6 @@ mod tests {
         progress_bar.finish_with_message("Done");
         assert_eq!(loop_count.load(Ordering::SeqCst), 1729);
     }
+
+    aoc_core::example_tests!(Day06);
 }
@@ -1,25 +1,40 @@
+use aoc_core::{input_path, parse_field, read_input, Answer, AocError, Solution};
 use itertools::{repeat_n, Itertools};
-use std::fs;
 
-pub fn load_input(file_path: &str) -> Vec<Equation> {
-    let text = fs::read_to_string(file_path).expect("Could not read from file");
+pub fn load_input(file_path: &str) -> Result<Vec<Equation>, AocError> {
+    let text = read_input(input_path!(file_path))?;
+    parse_input(&text)
+}
+
+pub fn parse_input(text: &str) -> Result<Vec<Equation>, AocError> {
     let mut equations = Vec::new();
 
-    for line in text.lines() {
-        let mut line_iter = line.split_terminator(':');
+    for (i, line) in text.lines().enumerate() {
+        let (test_value, numbers) = line.split_once(':').ok_or_else(|| {
+            AocError::parse(i + 1, line.len() + 1, line, "Expected ':' after test value")
+        })?;
+
         let equation = Equation {
-            test_value: line_iter.next().unwrap().parse().unwrap(),
-            numbers: line_iter
-                .next()
-                .unwrap()
+            test_value: parse_field(i, line, test_value)?,
+            numbers: numbers
                 .split_whitespace()
-                .map(|num| num.parse().unwrap())
-                .collect(),
+                .map(|num| parse_field(i, line, num))
+                .collect::<Result<_, _>>()?,
         };
+
+        if equation.numbers.is_empty() {
+            return Err(AocError::parse(
+                i + 1,
+                line.len() + 1,
+                line,
+                "Expected at least one number",
+            ));
+        }
+
         equations.push(equation);
     }
 
-    equations
+    Ok(equations)
 }
 
 #[derive(Clone, Debug)]
@@ -73,20 +88,40 @@ pub fn find_calibrated_equations(
     value
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<Equation>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(equations: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(find_calibrated_equations(equations, &[add, mul]).into())
+    }
+
+    fn part_2(equations: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(find_calibrated_equations(equations, &[add, mul, concat]).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn day_07_part_1_simple() {
-        let equations = load_input("input_simple.txt");
+        let equations = load_input("input_simple.txt").unwrap();
         let operations = vec![add, mul];
         assert_eq!(find_calibrated_equations(&equations, &operations), 3749);
     }
 
     #[test]
     fn day_07_part_1() {
-        let equations = load_input("input.txt");
+        let equations = load_input("input.txt").unwrap();
         let operations = vec![add, mul];
         assert_eq!(
             find_calibrated_equations(&equations, &operations),
@@ -96,18 +131,32 @@ mod tests {
 
     #[test]
     fn day_07_part_2_simple() {
-        let equations = load_input("input_simple.txt");
+        let equations = load_input("input_simple.txt").unwrap();
         let operations = vec![add, mul, concat];
         assert_eq!(find_calibrated_equations(&equations, &operations), 11387);
     }
 
     #[test]
     fn day_07_part_2() {
-        let equations = load_input("input.txt");
+        let equations = load_input("input.txt").unwrap();
         let operations = vec![add, mul, concat];
         assert_eq!(
             find_calibrated_equations(&equations, &operations),
             145149066755184
         );
     }
+
+    aoc_core::example_tests!(Day07);
+
+    #[test]
+    fn day_07_malformed_equation() {
+        assert!(matches!(
+            parse_input("190: 10 19\n3267 81 40 27").unwrap_err(),
+            AocError::Parse { line: 2, .. }
+        ));
+        assert!(matches!(
+            parse_input("190: 10 19\n3267:").unwrap_err(),
+            AocError::Parse { line: 2, .. }
+        ));
+    }
 }
@@ -1,26 +1,15 @@
+use aoc_core::{input_path, read_input, Answer, AocError, Solution};
+use aoc_grid::Grid;
 use ndarray::Array2;
 use std::collections::{HashMap, HashSet};
-use std::fs;
 
-fn load_input(file_path: &str) -> String {
-    fs::read_to_string(file_path).expect("Could not read from file")
+pub fn create_map(file_path: &str) -> Result<Array2<char>, AocError> {
+    let input = read_input(input_path!(file_path))?;
+    parse_map(&input)
 }
 
-pub fn create_map(file_path: &str) -> Array2<char> {
-    let input = load_input(file_path);
-    let rows: Vec<&str> = input.lines().collect();
-    let nrows = rows.len();
-    let ncols = rows[0].len();
-
-    let mut array = Array2::from_elem((nrows, ncols), ' ');
-
-    for (i, row) in rows.iter().enumerate() {
-        for (j, letter) in row.chars().enumerate() {
-            array[[i, j]] = letter;
-        }
-    }
-
-    array
+pub fn parse_map(input: &str) -> Result<Array2<char>, AocError> {
+    Ok(Grid::parse(input)?.into_array())
 }
 
 #[derive(Debug, Eq, Hash, PartialEq)]
@@ -165,35 +154,59 @@ fn greatest_common_divisor(a: isize, b: isize) -> isize {
     }
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = Array2<char>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_map(input)
+    }
+
+    fn part_1(antenna_map: &Self::Input) -> aoc_core::Result<Answer> {
+        let antenna_locations = locate_antennas(antenna_map);
+        Ok(find_antinodes(antenna_map, &antenna_locations).into())
+    }
+
+    fn part_2(antenna_map: &Self::Input) -> aoc_core::Result<Answer> {
+        let antenna_locations = locate_antennas(antenna_map);
+        Ok(find_antinodes_part_2(antenna_map, &antenna_locations).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn day_08_part_1_simple() {
-        let antenna_map = create_map("input_simple.txt");
+        let antenna_map = create_map("input_simple.txt").unwrap();
         let antenna_locations = locate_antennas(&antenna_map);
         assert_eq!(find_antinodes(&antenna_map, &antenna_locations), 14);
     }
 
     #[test]
     fn day_08_part_1() {
-        let antenna_map = create_map("input.txt");
+        let antenna_map = create_map("input.txt").unwrap();
         let antenna_locations = locate_antennas(&antenna_map);
         assert_eq!(find_antinodes(&antenna_map, &antenna_locations), 276);
     }
 
     #[test]
     fn day_08_part_2_simple() {
-        let antenna_map = create_map("input_simple.txt");
+        let antenna_map = create_map("input_simple.txt").unwrap();
         let antenna_locations = locate_antennas(&antenna_map);
         assert_eq!(find_antinodes_part_2(&antenna_map, &antenna_locations), 34);
     }
 
     #[test]
     fn day_08_part_2() {
-        let antenna_map = create_map("input.txt");
+        let antenna_map = create_map("input.txt").unwrap();
         let antenna_locations = locate_antennas(&antenna_map);
         assert_eq!(find_antinodes_part_2(&antenna_map, &antenna_locations), 991);
     }
+
+    aoc_core::example_tests!(Day08);
 }
@@ -1,7 +1,23 @@
-use std::{collections::HashSet, fs};
+use aoc_core::{input_path, read_input, Answer, AocError, Solution};
+use std::collections::HashSet;
 
-pub fn load_input(file_path: &str) -> String {
-    fs::read_to_string(file_path).expect("Could not read from file")
+pub fn load_input(file_path: &str) -> Result<String, AocError> {
+    read_input(input_path!(file_path))
+}
+
+/// Checks that the disk map is a single line of digits.
+pub fn parse_disk_map(input: &str) -> Result<String, AocError> {
+    let disk_map = input.trim();
+
+    if let Some((column, _)) = disk_map
+        .chars()
+        .enumerate()
+        .find(|(_, c)| !c.is_ascii_digit())
+    {
+        return Err(AocError::parse(1, column + 1, disk_map, "Expected a digit"));
+    }
+
+    Ok(disk_map.to_string())
 }
 
 pub fn checksum(disk_map: &str) -> usize {
@@ -147,31 +163,62 @@ pub fn checksum_part_2(disk_map: &str) -> usize {
         .sum()
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Input = String;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_disk_map(input)
+    }
+
+    fn part_1(disk_map: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(checksum(disk_map).into())
+    }
+
+    fn part_2(disk_map: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(checksum_part_2(disk_map).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn day_09_part_1_simple() {
-        let input = load_input("input_simple.txt");
+        let input = load_input("input_simple.txt").unwrap();
         assert_eq!(checksum(&input), 1928);
     }
 
     #[test]
     fn day_09_part_1() {
-        let input = load_input("input.txt");
+        let input = load_input("input.txt").unwrap();
         assert_eq!(checksum(&input), 6607511583593);
     }
 
     #[test]
     fn day_09_part_2_simple() {
-        let input = load_input("input_simple.txt");
+        let input = load_input("input_simple.txt").unwrap();
         assert_eq!(checksum_part_2(&input), 2858);
     }
 
     #[test]
     fn day_09_part_2() {
-        let input = load_input("input.txt");
+        let input = load_input("input.txt").unwrap();
         assert_eq!(checksum_part_2(&input), 6636608781232);
     }
+
+    aoc_core::example_tests!(Day09);
+
+    #[test]
+    fn day_09_malformed_disk_map() {
+        assert_eq!(parse_disk_map("2333133121414131402\n").unwrap().len(), 19);
+        assert!(matches!(
+            parse_disk_map("23331x3121414131402").unwrap_err(),
+            AocError::Parse { column: 6, .. }
+        ));
+    }
 }
@@ -1,32 +1,23 @@
+use aoc_core::{input_path, read_input, Answer, AocError, Solution};
+use aoc_grid::{Grid, DIRECTIONS_4};
 use ndarray::Array2;
 use std::collections::HashSet;
-use std::fs;
 
-const DIRECTIONS: [(i8, i8); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
-
-fn load_input(file_path: &str) -> String {
-    fs::read_to_string(file_path).expect("Could not read from file")
+pub fn create_map(file_path: &str) -> Result<Array2<usize>, AocError> {
+    let input = read_input(input_path!(file_path))?;
+    parse_map(&input)
 }
 
-pub fn create_map(file_path: &str) -> Array2<usize> {
-    let input = load_input(file_path);
-    let rows: Vec<&str> = input.lines().collect();
-    let nrows = rows.len();
-    let ncols = rows[0].len();
-
-    let mut array = Array2::from_elem((nrows, ncols), 0);
-
-    for (i, row) in rows.iter().enumerate() {
-        for (j, letter) in row.chars().enumerate() {
-            if letter == '.' {
-                array[[i, j]] = 20;
-            } else {
-                array[[i, j]] = letter.to_string().parse::<usize>().unwrap();
-            }
+pub fn parse_map(input: &str) -> Result<Array2<usize>, AocError> {
+    let grid = Grid::parse_with(input, |letter| {
+        if letter == '.' {
+            Ok(20)
+        } else {
+            letter.to_string().parse::<usize>()
         }
-    }
+    })?;
 
-    array
+    Ok(grid.into_array())
 }
 
 pub fn find_trailheads(map: &Array2<usize>) -> HashSet<(usize, usize)> {
@@ -81,9 +72,9 @@ pub fn search_next_position(
     let mut paths = 0;
     let next_value = current_value + 1;
 
-    for (move_row, move_column) in DIRECTIONS {
-        let new_row = (current_row as i8 + move_row) as usize;
-        let new_column = (current_column as i8 + move_column) as usize;
+    for (move_row, move_column) in DIRECTIONS_4 {
+        let new_row = current_row.wrapping_add_signed(move_row);
+        let new_column = current_column.wrapping_add_signed(move_column);
         paths += search_next_position(map, (new_row, new_column), next_value, visited, reached);
     }
 
@@ -91,13 +82,41 @@ pub fn search_next_position(
     paths
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Array2<usize>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_map(input)
+    }
+
+    fn part_1(map: &Self::Input) -> aoc_core::Result<Answer> {
+        let total_paths: usize = find_trailheads(map)
+            .into_iter()
+            .map(|trailhead| count_paths(map, trailhead))
+            .sum();
+        Ok(total_paths.into())
+    }
+
+    fn part_2(map: &Self::Input) -> aoc_core::Result<Answer> {
+        let total_paths: usize = find_trailheads(map)
+            .into_iter()
+            .map(|trailhead| rate_paths(map, trailhead))
+            .sum();
+        Ok(total_paths.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn day_10_part_1_simple() {
-        let map = create_map("input_simple.txt");
+        let map = create_map("input_simple.txt").unwrap();
         let trailheads = find_trailheads(&map);
         let mut total_paths = 0;
 
@@ -110,7 +129,7 @@ mod tests {
 
     #[test]
     fn day_10_part_1() {
-        let map = create_map("input.txt");
+        let map = create_map("input.txt").unwrap();
         let trailheads = find_trailheads(&map);
         let mut total_paths = 0;
 
@@ -123,7 +142,7 @@ mod tests {
 
     #[test]
     fn day_10_part_2_simple() {
-        let map = create_map("input_simple.txt");
+        let map = create_map("input_simple.txt").unwrap();
         let trailheads = find_trailheads(&map);
         let mut total_paths = 0;
 
@@ -136,7 +155,7 @@ mod tests {
 
     #[test]
     fn day_10_part_2() {
-        let map = create_map("input.txt");
+        let map = create_map("input.txt").unwrap();
         let trailheads = find_trailheads(&map);
         let mut total_paths = 0;
 
@@ -146,4 +165,19 @@ mod tests {
 
         assert_eq!(total_paths, 1925);
     }
+
+    aoc_core::example_tests!(Day10);
+
+    #[test]
+    fn day_10_malformed_map() {
+        let err = parse_map("0123\n45x7\n").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: 3,
+                ..
+            }
+        ));
+    }
 }
@@ -1,16 +1,19 @@
+use aoc_core::{input_path, parse_fields, read_input, Answer, AocError, Solution};
 use count_digits::CountDigits;
 use counter::Counter;
-use std::fs;
 
-pub fn load_input(file_path: &str) -> Vec<usize> {
-    let text = fs::read_to_string(file_path).expect("Could not read from file");
+pub fn load_input(file_path: &str) -> Result<Vec<usize>, AocError> {
+    let text = read_input(input_path!(file_path))?;
+    parse_input(&text)
+}
+
+pub fn parse_input(text: &str) -> Result<Vec<usize>, AocError> {
     let mut numbers = Vec::new();
-    text.split_whitespace()
-        .collect::<Vec<&str>>()
-        .iter()
-        .for_each(|n| numbers.push(n.parse::<usize>().unwrap()));
+    for (i, line) in text.lines().enumerate() {
+        numbers.extend(parse_fields::<usize>(i, line)?);
+    }
 
-    numbers
+    Ok(numbers)
 }
 
 fn string_and_split(num: usize) -> [usize; 2] {
@@ -26,7 +29,7 @@ fn string_and_split(num: usize) -> [usize; 2] {
 fn transform_stone(stone: usize) -> Vec<usize> {
     if stone == 0 {
         vec![1]
-    } else if stone.count_digits() % 2 == 0 {
+    } else if stone.count_digits().is_multiple_of(2) {
         string_and_split(stone).to_vec()
     } else {
         vec![stone * 2024]
@@ -49,6 +52,26 @@ pub fn blink(stones: &[usize], num_blinks: usize) -> usize {
     stones.values().sum()
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(stones: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(blink(stones, 25).into())
+    }
+
+    fn part_2(stones: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(blink(stones, 75).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,13 +85,15 @@ mod tests {
 
     #[test]
     fn day_11_part_1() {
-        let stones = load_input("input.txt");
+        let stones = load_input("input.txt").unwrap();
         assert_eq!(blink(&stones, 25), 194782);
     }
 
     #[test]
     fn day_11_part_2() {
-        let stones = load_input("input.txt");
+        let stones = load_input("input.txt").unwrap();
         assert_eq!(blink(&stones, 75), 233007586663131);
     }
+
+    aoc_core::example_tests!(Day11);
 }
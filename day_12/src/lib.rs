@@ -1,103 +1,102 @@
-use std::fs;
+use aoc_core::{input_path, read_input, Answer, AocError, Solution};
+use aoc_grid::Grid;
+use ndarray::Array2;
 
-const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
-
-pub fn load_input(file_path: &str) -> Vec<Vec<char>> {
-    let text = fs::read_to_string(file_path).expect("Could not read from file");
-    let mut map = Vec::new();
-
-    for line in text.lines() {
-        let row: Vec<char> = line.chars().collect();
-        map.push(row);
-    }
+pub fn load_input(file_path: &str) -> Result<Grid<char>, AocError> {
+    let text = read_input(input_path!(file_path))?;
+    parse_input(&text)
+}
 
-    map
+pub fn parse_input(text: &str) -> Result<Grid<char>, AocError> {
+    Grid::parse(text)
 }
 
 fn find_region(
-    map: &[Vec<char>],
-    visited: &mut [Vec<bool>],
-    i: usize,
-    j: usize,
-    plant: char,
+    map: &Grid<char>,
+    visited: &mut Grid<bool>,
+    start: (usize, usize),
 ) -> (usize, usize) {
-    let mut coordinates = vec![(i, j)];
+    let plant = map[start];
+    let mut coordinates = vec![start];
     let mut area = 0;
     let mut perimeter = 0;
 
-    while let Some((x, y)) = coordinates.pop() {
-        if visited[x][y] || map[x][y] != plant {
+    while let Some(position) = coordinates.pop() {
+        if visited[position] {
             continue;
         }
 
-        visited[x][y] = true;
+        visited[position] = true;
         area += 1;
 
-        for (dx, dy) in DIRECTIONS.iter() {
-            let new_x = x as isize + dx;
-            let new_y = y as isize + dy;
-
-            if new_x < 0
-                || new_y < 0
-                || new_x >= map.len() as isize
-                || new_y >= map[0].len() as isize
-                || map[new_x as usize][new_y as usize] != plant
-            {
-                perimeter += 1;
-            } else if !visited[new_x as usize][new_y as usize] {
-                coordinates.push((new_x as usize, new_y as usize));
-            }
-        }
+        // Every side not shared with the same plant, including the map's
+        // edge, needs a fence.
+        let same_plant: Vec<(usize, usize)> = map
+            .neighbours_4(position)
+            .filter(|&neighbour| map[neighbour] == plant)
+            .collect();
+        perimeter += 4 - same_plant.len();
+
+        coordinates.extend(
+            same_plant
+                .into_iter()
+                .filter(|&neighbour| !visited[neighbour]),
+        );
     }
     (area, perimeter)
 }
 
-pub fn calculate_fencing_price(map: &[Vec<char>]) -> usize {
-    let mut visited = vec![vec![false; map[0].len()]; map.len()];
+pub fn calculate_fencing_price(map: &Grid<char>) -> usize {
+    let mut visited = Grid::from_array(Array2::from_elem((map.nrows(), map.ncols()), false));
     let mut total_price = 0;
 
-    for i in 0..map.len() {
-        for j in 0..map[0].len() {
-            if !visited[i][j] {
-                let (area, perimeter) = find_region(map, &mut visited, i, j, map[i][j]);
-                total_price += area * perimeter;
-            }
+    for (position, _) in map.indexed_iter() {
+        if !visited[position] {
+            let (area, perimeter) = find_region(map, &mut visited, position);
+            total_price += area * perimeter;
         }
     }
 
     total_price
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(map: &Self::Input) -> aoc_core::Result<Answer> {
+        Ok(calculate_fencing_price(map).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn day_12_part_1_simple() {
-        let map = vec![
-            vec!['A', 'A', 'A', 'A'],
-            vec!['B', 'B', 'C', 'D'],
-            vec!['B', 'B', 'C', 'C'],
-            vec!['E', 'E', 'E', 'C'],
-        ];
+        let map = parse_input("AAAA\nBBCD\nBBCC\nEEEC").unwrap();
         assert_eq!(calculate_fencing_price(&map), 140);
 
-        let map = vec![
-            vec!['O', 'O', 'O', 'O', 'O'],
-            vec!['O', 'X', 'O', 'X', 'O'],
-            vec!['O', 'O', 'O', 'O', 'O'],
-            vec!['O', 'X', 'O', 'X', 'O'],
-            vec!['O', 'O', 'O', 'O', 'O'],
-        ];
+        let map = parse_input("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO").unwrap();
         assert_eq!(calculate_fencing_price(&map), 772);
 
-        let map = load_input("input_simple.txt");
+        let map = load_input("input_simple.txt").unwrap();
         assert_eq!(calculate_fencing_price(&map), 1930);
     }
 
     #[test]
     fn day_12_part_1() {
-        let map = load_input("input.txt");
+        let map = load_input("input.txt").unwrap();
         assert_eq!(calculate_fencing_price(&map), 1550156);
     }
+
+    aoc_core::example_tests!(Day12);
 }